@@ -5,7 +5,7 @@ mod test {
 
     use rbatis::core::value::DateTimeNow;
     use rbatis::core::Error;
-    use rbatis::crud::{CRUDMut, CRUD, Skip};
+    use rbatis::crud::{Agg, ChunkProgress, CRUDMut, CRUD, Skip};
     use rbatis::plugin::logic_delete::{RbatisLogicDeletePlugin, TableNoLogic};
     use rbatis::plugin::page::{Page, PageRequest};
     use rbatis::plugin::snowflake::new_snowflake_id;
@@ -319,6 +319,81 @@ mod test {
         println!("{}", serde_json::to_string(&r).unwrap());
     }
 
+    #[tokio::test]
+    pub async fn test_fetch_stream_by_wrapper() {
+        use futures::StreamExt;
+        let rb = init_rbatis().await;
+        let w = rb.new_wrapper().eq("delete_flag", 1);
+        let mut stream = rb.fetch_stream_by_wrapper::<BizActivity>(&w, 100);
+        while let Some(item) = stream.next().await {
+            println!("{:?}", item.unwrap());
+        }
+    }
+
+    #[tokio::test]
+    pub async fn test_fetch_aggregate() {
+        #[derive(serde::Deserialize, Debug)]
+        pub struct Stat {
+            pub count: u64,
+            pub max_create_time: Option<String>,
+            pub count_distinct_status: u64,
+        }
+        let rb = init_rbatis().await;
+        let w = rb.new_wrapper().eq("delete_flag", 1);
+        let stat: Stat = rb.fetch_aggregate::<BizActivity, _>(&w, &[Agg::Count("*"), Agg::Max("create_time"), Agg::CountDistinct("status")]).await.unwrap();
+        println!("{:?}", stat);
+        let max_status: Option<i32> = rb.fetch_max::<BizActivity, _>(&w, "status").await.unwrap();
+        println!("{:?}", max_status);
+
+        #[derive(serde::Deserialize, Debug)]
+        pub struct Count {
+            pub count: u64,
+        }
+        let w = rb.new_wrapper().group_by(&["status"]).having("count(1) > 0");
+        let groups: Vec<(Option<i32>, Count)> = rb.fetch_group_aggregate::<BizActivity, _, _>(&w, &[Agg::Count("*")]).await.unwrap();
+        println!("{:?}", groups);
+    }
+
+    #[tokio::test]
+    pub async fn test_fetch_list_by_join() {
+        let rb = init_rbatis().await;
+        let w = rb.new_wrapper_table::<BizActivity>()
+            .alias("a")
+            .left_join_as::<BizActivity>("b", "a.id = b.id")
+            .eq("a.delete_flag", 1);
+        let list: Vec<(BizActivity, Option<BizActivity>)> = rb.fetch_list_by_join::<BizActivity, _>(&w).await.unwrap();
+        println!("{:?}", list);
+        let page: Page<(BizActivity, Option<BizActivity>)> = rb.fetch_page_by_join::<BizActivity, _>(&w, &PageRequest::new(1, 20)).await.unwrap();
+        println!("{:?}", page);
+    }
+
+    #[tokio::test]
+    pub async fn test_for_each_chunk() {
+        let rb = init_rbatis().await;
+        let w = rb.new_wrapper().eq("delete_flag", 1);
+        let progress = rb
+            .for_each_chunk::<BizActivity, _, _>(&w, 2, |batch| async move {
+                println!("chunk len:{}", batch.len());
+                Ok(())
+            })
+            .await
+            .unwrap();
+        println!("progress:{:?}", progress);
+
+        //resume from last key, commit each chunk
+        let mut progress = ChunkProgress::resume("1");
+        rb.for_each_chunk_tx::<BizActivity, _>(&w, "id", 2, &mut progress, |batch, tx| Box::pin(async move {
+            for mut x in batch {
+                x.remark = Some("chunk".to_string());
+                tx.update_by_column("id", &mut x).await?;
+            }
+            Ok(())
+        }))
+            .await
+            .unwrap();
+        println!("progress:{:?}", progress);
+    }
+
     #[tokio::test]
     pub async fn test_list() {
         let mut rb = init_rbatis().await;
@@ -328,6 +403,67 @@ mod test {
         println!("{}", serde_json::to_string(&r).unwrap());
     }
 
+    /// BizActivity with lifecycle hooks
+    #[crud_table(table_name:"biz_activity" | before_insert:"validate" | before_update:"trim_id" | after_fetch:"decode")]
+    #[derive(Clone, Debug)]
+    pub struct BizActivityHook {
+        pub id: Option<String>,
+        pub name: Option<String>,
+        pub remark: Option<String>,
+        pub version: Option<BigDecimal>,
+        pub delete_flag: Option<i32>,
+    }
+
+    impl BizActivityHook {
+        async fn validate<E>(&self, _executor: &mut E) -> rbatis::Result<Option<Self>>
+            where E: rbatis::executor::ExecutorMut + Send + ?Sized {
+            match &self.name {
+                Some(name) if !name.trim().is_empty() => {
+                    let mut table = self.clone();
+                    table.name = Some(name.trim().to_string());
+                    Ok(Some(table))
+                }
+                _ => Err(Error::from("name can not be empty")),
+            }
+        }
+
+        async fn trim_id<E>(&mut self, _executor: &mut E) -> rbatis::Result<()>
+            where E: rbatis::executor::ExecutorMut + Send + ?Sized {
+            self.id = self.id.as_ref().map(|x| x.trim().to_string());
+            Ok(())
+        }
+
+        async fn decode<E>(&mut self, _executor: &mut E) -> rbatis::Result<()>
+            where E: rbatis::executor::ExecutorMut + Send + ?Sized {
+            self.remark = Some(format!("fetched:{}", self.remark.clone().unwrap_or_default()));
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    pub async fn test_save_hook() {
+        let rb = init_rbatis().await;
+        let mut activity = BizActivityHook {
+            id: Some("hook-1".to_string()),
+            name: Some("   ".to_string()),
+            remark: None,
+            version: Some(BigDecimal::from(1)),
+            delete_flag: Some(1),
+        };
+        rb.remove_by_column::<BizActivityHook, _>("id", &"hook-1").await.unwrap();
+        //before_insert return Err,abort the save
+        assert!(rb.save(&activity, &[]).await.is_err());
+        activity.name = Some(" hook ".to_string());
+        rb.save(&activity, &[]).await.unwrap();
+        let r: BizActivityHook = rb.fetch_by_column("id", &"hook-1").await.unwrap();
+        assert_eq!(r.name, Some("hook".to_string()));
+        assert_eq!(r.remark, Some("fetched:".to_string()));
+        //update_by_column read the id after before_update
+        activity.id = Some(" hook-1 ".to_string());
+        activity.remark = Some("updated".to_string());
+        assert_eq!(rb.update_by_column("id", &mut activity).await.unwrap(), 1);
+    }
+
     #[test]
     pub fn test_is_debug() {
         let rb = Rbatis::new();
@@ -12,10 +12,9 @@ mod test {
             .await
             .unwrap();
 
-        let mut w = rb.new_wrapper();
         //replace your dyn table name
-        w.formats
-            .insert("table_name".to_string(), |s|"biz_activity".to_string());
+        let w = rb.new_wrapper()
+            .set_format("table_name", |s| "biz_activity".to_string());
         //support all of RB.*_wrapper() method
         let r = rb.fetch_list_by_wrapper::<BizActivity>(&w).await;
         if r.is_err() {
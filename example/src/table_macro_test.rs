@@ -70,4 +70,17 @@ mod test {
         println!("{:#?}", names);
         assert_eq!(names.len(), table_vec.len());
     }
+
+    #[test]
+    fn test_table_columns() {
+        use rbatis::core::db::DriverType;
+        use rbatis::wrapper::Wrapper;
+        let w = Wrapper::new(&DriverType::Mysql)
+            .eq(BizActivity::COLUMNS.name, "a")
+            .ge(BizActivity::COLUMNS.status, 1)
+            .order_by(false, &[&BizActivity::COLUMNS.create_time]);
+        println!("{}", w.sql);
+        assert_eq!(BizActivity::COLUMNS.delete_flag.name(), "delete_flag");
+        assert_eq!(w.sql.trim(), "name = ? and status >= ? order by create_time desc");
+    }
 }
@@ -9,14 +9,16 @@ use crate::proc_macro::TokenStream;
 use crate::macros::crud_table_impl::{impl_crud_driver, impl_crud};
 use crate::macros::sql_impl::impl_macro_sql;
 use crate::macros::py_sql_impl::{impl_macro_py_sql, impl_macro_html_sql};
+use crate::macros::wrapper_filter_impl::impl_wrapper_filter;
 
 mod macros;
 mod util;
 
-#[proc_macro_derive(CRUDTable)]
+/// impl CRUDTable,field attribute #[crud_table(sensitive)] is supported(see crud_table)
+#[proc_macro_derive(CRUDTable, attributes(crud_table))]
 pub fn hello_macro_derive(input: TokenStream) -> TokenStream {
     let ast = syn::parse(input).unwrap();
-    let stream = impl_crud_driver(&ast, "", "", &HashMap::new());
+    let stream = impl_crud_driver(&ast, "", "", &HashMap::new(), &HashMap::new(), false);
     #[cfg(feature = "debug_mode")]
     {
         println!("............gen impl CRUDTable:\n {}", stream);
@@ -26,6 +28,30 @@ pub fn hello_macro_derive(input: TokenStream) -> TokenStream {
     stream
 }
 
+/// impl WrapperFilter for a query struct,None fields will be skip
+/// for example:
+///     #[derive(WrapperFilter)]
+///     pub struct ActivityQuery {
+///         #[filter(op = "like")]
+///         pub name: Option<String>,
+///         #[filter(op = "ge", column = "create_time")]
+///         pub create_time_start: Option<NaiveDateTime>,
+///         #[filter(op = "in", column = "status")]
+///         pub status_in: Option<Vec<i32>>,
+///     }
+#[proc_macro_derive(WrapperFilter, attributes(filter))]
+pub fn wrapper_filter_derive(input: TokenStream) -> TokenStream {
+    let ast = syn::parse(input).unwrap();
+    let stream = impl_wrapper_filter(&ast);
+    #[cfg(feature = "debug_mode")]
+    {
+        println!("............gen impl WrapperFilter:\n {}", stream);
+        println!("............gen impl WrapperFilter end............");
+    }
+
+    stream
+}
+
 /// auto create sql macro,this macro use RB.fetch_prepare and RB.exec_prepare
 /// for example:
 ///     #[sql(RB, "select * from biz_activity where id = ?")]
@@ -90,8 +116,10 @@ pub fn html_sql(args: TokenStream, func: TokenStream) -> TokenStream {
 /// #[crud_table]
 /// #[crud_table(table_name:"biz_activity")]
 /// #[crud_table(table_name:"biz_activity" | table_columns:"id,name,version,delete_flag" | formats_pg:"id:{}::uuid,name:{}::string")]
+/// #[crud_table(before_insert:"validate" | after_fetch:"decode")] //lifecycle hooks,see CRUDTable
 /// pub struct BizActivity {
 ///   pub id: Option<String>,
+///   #[crud_table(sensitive)] //mask in sql log,see RedactPolicy
 ///   pub name: Option<String>,
 ///   pub version: Option<i32>,
 ///   pub delete_flag: Option<i32>,
@@ -13,6 +13,8 @@ pub(crate) fn impl_crud_driver(
     arg_table_name: &str,
     arg_table_columns: &str,
     arg_formats: &HashMap<String, String>,
+    arg_hooks: &HashMap<String, String>,
+    arg_typed_columns: bool,
 ) -> TokenStream {
     let name = &ast.ident;
     let table_name;
@@ -72,6 +74,18 @@ pub(crate) fn impl_crud_driver(
             _ => { serde_json::Value::Null }
         }
     };
+    let columns = if arg_typed_columns {
+        gen_columns(ast, &arg_table_columns_vec, !arg_table_columns.is_empty())
+    } else {
+        quote! {}
+    };
+    let sensitive_columns = gen_sensitive_columns(&ast.data);
+    let hooks = gen_hooks(arg_hooks);
+    let async_trait = if arg_hooks.is_empty() {
+        quote! {}
+    } else {
+        quote! { #[rbatis::async_trait] }
+    };
 
 
     let mut formats_mysql = proc_macro2::TokenStream::new();
@@ -100,8 +114,13 @@ pub(crate) fn impl_crud_driver(
 
 
     let gen = quote! {
+        #columns
+
+        #async_trait
         impl rbatis::crud::CRUDTable for #name {
 
+            #hooks
+
             fn get(&self, column: &str) -> serde_json::Value {
                 #get_matchs
             }
@@ -114,6 +133,10 @@ pub(crate) fn impl_crud_driver(
                  #fields
             }
 
+            fn sensitive_columns() -> Vec<String> {
+                 vec![#(#sensitive_columns.to_string()),*]
+            }
+
             fn formats(driver_type: &rbatis::core::db::DriverType) -> std::collections::HashMap<String, fn(arg:&str)->String> {
                   let mut m: std::collections::HashMap<String, fn(arg:&str)->String> = std::collections::HashMap::new();
                   match driver_type{
@@ -223,6 +246,126 @@ fn find_format_string(arg: &str) -> Vec<(String, String)> {
     return list;
 }
 
+/// gen typed column constants(only #[crud_table],not #[derive(CRUDTable)]),for example:
+///   pub struct BizActivityColumns { pub id: rbatis::column::Column<BizActivity, Option<String>>, ... }
+///   impl BizActivity { pub const COLUMNS: BizActivityColumns = BizActivityColumns { id: rbatis::column::Column::new("id"), ... }; }
+/// fields are matched to table_columns by name,fields not in table_columns have no typed column,
+/// and a column of table_columns without field is a compile error.
+fn gen_columns(ast: &syn::DeriveInput, custom_columns: &Vec<&str>, use_custom_columns: bool) -> proc_macro2::TokenStream {
+    let name = &ast.ident;
+    let vis = &ast.vis;
+    let columns_ident = Ident::new(&format!("{}Columns", name), name.span());
+    let custom_columns: Vec<&str> = custom_columns.iter().map(|x| un_packing_column(x)).collect();
+    let mut fields = quote! {};
+    let mut values = quote! {};
+    let mut field_columns = vec![];
+    match &ast.data {
+        syn::Data::Struct(s) => {
+            for field in &s.fields {
+                match &field.ident {
+                    None => {}
+                    Some(ident) => {
+                        let ty = &field.ty;
+                        let column = ident.to_string().trim_start_matches("r#").to_string();
+                        field_columns.push(column.clone());
+                        if use_custom_columns && !custom_columns.contains(&column.as_str()) {
+                            continue;
+                        }
+                        fields = quote! {
+                            #fields
+                            pub #ident: rbatis::column::Column<#name, #ty>,
+                        };
+                        values = quote! {
+                            #values
+                            #ident: rbatis::column::Column::new(#column),
+                        };
+                    }
+                }
+            }
+        }
+        _ => {
+            panic!("[rbatis] #[crud_table] only support struct for crud_table's macro!")
+        }
+    }
+    if use_custom_columns {
+        for column in &custom_columns {
+            if !field_columns.iter().any(|x| x == column) {
+                let msg = format!("[rbatis] #[crud_table] table_columns:'{}' is not a field of {}!", column, name);
+                return quote! {
+                    compile_error!(#msg);
+                };
+            }
+        }
+    }
+    let doc = format!("typed columns of {}", name);
+    quote! {
+        #[doc = #doc]
+        #[derive(Clone, Copy, Debug)]
+        #vis struct #columns_ident {
+            #fields
+        }
+
+        impl #name {
+            /// typed columns,can be used in Wrapper
+            pub const COLUMNS: #columns_ident = #columns_ident {
+                #values
+            };
+        }
+    }
+}
+
+/// " `name` " => "name"
+fn un_packing_column(column: &str) -> &str {
+    column.trim().trim_matches(|c| c == '`' || c == '"' || c == '[' || c == ']')
+}
+
+/// the names of lifecycle hooks,see CRUDTable
+const HOOKS: [&str; 6] = ["before_insert", "after_insert", "before_update", "after_update", "before_delete", "after_fetch"];
+
+/// gen the hook methods call the methods point by #[crud_table(before_insert:"validate")],for example:
+///   async fn before_insert<E>(&self, executor: &mut E) -> rbatis::Result<Option<Self>> { self.validate(executor).await }
+fn gen_hooks(hooks: &HashMap<String, String>) -> proc_macro2::TokenStream {
+    let mut methods = quote! {};
+    for (hook, method) in hooks {
+        let hook_ident = Ident::new(hook, proc_macro2::Span::call_site());
+        let method_ident = Ident::new(method.trim(), proc_macro2::Span::call_site());
+        let method = if hook == "before_delete" {
+            quote! {
+                async fn #hook_ident<E>(executor: &mut E, w: &rbatis::wrapper::Wrapper) -> rbatis::Result<()>
+                    where E: rbatis::executor::ExecutorMut + Send + ?Sized {
+                    Self::#method_ident(executor, w).await
+                }
+            }
+        } else if hook == "before_insert" {
+            quote! {
+                async fn #hook_ident<E>(&self, executor: &mut E) -> rbatis::Result<Option<Self>>
+                    where E: rbatis::executor::ExecutorMut + Send + ?Sized {
+                    self.#method_ident(executor).await
+                }
+            }
+        } else if hook == "after_insert" {
+            quote! {
+                async fn #hook_ident<E>(&self, executor: &mut E) -> rbatis::Result<()>
+                    where E: rbatis::executor::ExecutorMut + Send + ?Sized {
+                    self.#method_ident(executor).await
+                }
+            }
+        } else {
+            quote! {
+                async fn #hook_ident<E>(&mut self, executor: &mut E) -> rbatis::Result<()>
+                    where E: rbatis::executor::ExecutorMut + Send + ?Sized {
+                    self.#method_ident(executor).await
+                }
+            }
+        };
+        methods = quote! {
+            #methods
+            #method
+        };
+    }
+    methods
+}
+
 fn gen_table_name(data: &syn::Ident) -> String {
     let mut table_name = data.to_string();
     let names: Vec<&str> = table_name.split("::").collect();
@@ -252,6 +395,50 @@ fn gen_fields(data: &syn::Data) -> Vec<Ident> {
     fields
 }
 
+/// the columns of fields mark by #[crud_table(sensitive)]
+fn gen_sensitive_columns(data: &syn::Data) -> Vec<String> {
+    let mut columns = vec![];
+    if let syn::Data::Struct(s) = data {
+        for field in &s.fields {
+            let ident = match &field.ident {
+                None => {
+                    continue;
+                }
+                Some(v) => v,
+            };
+            if !field.attrs.iter().any(is_sensitive_attr) {
+                continue;
+            }
+            //the column is the field name,same as the json key of the table
+            columns.push(ident.to_string().trim_start_matches("r#").to_string());
+        }
+    }
+    columns
+}
+
+/// is #[crud_table(sensitive)]
+fn is_sensitive_attr(attr: &syn::Attribute) -> bool {
+    if !attr.path.is_ident("crud_table") {
+        return false;
+    }
+    match attr.parse_meta() {
+        Ok(syn::Meta::List(list)) => list.nested.iter().any(|x| match x {
+            syn::NestedMeta::Meta(syn::Meta::Path(p)) => p.is_ident("sensitive"),
+            _ => false,
+        }),
+        _ => false,
+    }
+}
+
+/// remove the field attributes #[crud_table(..)],they are only read by this macro
+fn remove_field_attrs(ast: &mut syn::DeriveInput) {
+    if let syn::Data::Struct(s) = &mut ast.data {
+        for field in s.fields.iter_mut() {
+            field.attrs.retain(|x| !x.path.is_ident("crud_table"));
+        }
+    }
+}
+
 fn gen_fields_names(data: &Vec<Ident>) -> String {
     let mut fields = String::new();
     let mut index = 0;
@@ -291,6 +478,8 @@ pub struct CrudEnableConfig {
     pub table_name: String,
     pub table_columns: String,
     pub formats: HashMap<String, String>,
+    /// lifecycle hook name -> method name
+    pub hooks: HashMap<String, String>,
 }
 
 /// impl the crud macro
@@ -298,19 +487,21 @@ pub(crate) fn impl_crud(args: TokenStream, input: TokenStream) -> TokenStream {
     let arg_str = args.to_string();
     let config = read_config(&arg_str);
     let token_string = input.to_string();
-    let input_clone: proc_macro2::TokenStream = input.clone().into();
     let driver_token = gen_driver_token(&token_string);
-    let ast = syn::parse(input).unwrap();
+    let mut ast = syn::parse(input).unwrap();
     let stream = impl_crud_driver(
         &ast,
         &config.table_name,
         &config.table_columns,
         &config.formats,
+        &config.hooks,
+        true,
     );
+    remove_field_attrs(&mut ast);
     let s: proc_macro2::TokenStream = stream.into();
     let qt = quote! {
        #driver_token
-       #input_clone
+       #ast
        #s
     };
     qt.into()
@@ -337,7 +528,8 @@ fn gen_driver_token(token_string: &str) -> proc_macro2::TokenStream {
 ///
 ///     table_name:"biz_activity"|
 ///     table_columns:"id,name,version,delete_flag"|
-///     formats_pg:"id:{}::uuid,name:{}::string"
+///     formats_pg:"id:{}::uuid,name:{}::string"|
+///     before_insert:"validate"|after_fetch:"decode"
 fn read_config(arg: &str) -> CrudEnableConfig {
     let keys: Vec<&str> = arg.split("|").collect();
     let mut map = HashMap::new();
@@ -371,6 +563,15 @@ fn read_config(arg: &str) -> CrudEnableConfig {
             formats.insert(k.to_owned(), v.to_owned());
         }
     }
+    let mut hooks = HashMap::new();
+    for (k, v) in &map {
+        if k.starts_with("before_") || k.starts_with("after_") {
+            if !HOOKS.contains(&k.as_str()) {
+                panic!("[rbatis] #[crud_table] hook must be one of {}!", HOOKS.join(","));
+            }
+            hooks.insert(k.to_owned(), v.to_owned());
+        }
+    }
     return CrudEnableConfig {
         table_name: map.get("table_name").unwrap_or(&String::new()).to_string(),
         table_columns: map
@@ -378,5 +579,55 @@ fn read_config(arg: &str) -> CrudEnableConfig {
             .unwrap_or(&String::new())
             .to_string(),
         formats: formats,
+        hooks: hooks,
     };
 }
+
+#[cfg(test)]
+mod test {
+    use super::{gen_columns, gen_hooks, read_config};
+
+    #[test]
+    fn test_read_config_hooks() {
+        let config = read_config("table_name:\"biz_activity\"|before_insert:\"validate\"|\n after_fetch: decode |before_delete:\"check_delete\"");
+        assert_eq!(config.table_name, "biz_activity");
+        assert_eq!(config.hooks.len(), 3);
+        assert_eq!(config.hooks.get("before_insert").unwrap(), "validate");
+        assert_eq!(config.hooks.get("after_fetch").unwrap(), "decode");
+        assert_eq!(config.hooks.get("before_delete").unwrap(), "check_delete");
+        assert!(read_config("table_name:biz_activity").hooks.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "hook must be one of")]
+    fn test_read_config_unknown_hook() {
+        read_config("before_save:\"validate\"");
+    }
+
+    #[test]
+    fn test_gen_columns() {
+        let ast: syn::DeriveInput = syn::parse_str("pub struct BizActivity { pub id: Option<String>, pub name: Option<String>, pub r#type: Option<i32> }").unwrap();
+        let code = gen_columns(&ast, &vec!["type", "`id`"], true).to_string().replace(' ', "");
+        assert!(code.contains("id:rbatis::column::Column::new(\"id\")"));
+        assert!(code.contains("r#type:rbatis::column::Column::new(\"type\")"));
+        assert!(!code.contains("name"));
+        let code = gen_columns(&ast, &vec!["id", "nmae"], true).to_string();
+        assert!(code.contains("compile_error"));
+        assert!(code.contains("table_columns:'nmae' is not a field of BizActivity"));
+    }
+
+    #[test]
+    fn test_gen_hooks() {
+        let config = read_config("before_insert:validate|before_delete:check_delete");
+        let code = gen_hooks(&config.hooks).to_string().replace(' ', "");
+        assert!(code.contains("asyncfnbefore_insert<E>(&self,executor:&mutE)->rbatis::Result<Option<Self>>"));
+        assert!(code.contains("self.validate(executor).await"));
+        assert!(code.contains("asyncfnbefore_delete<E>(executor:&mutE,w:&rbatis::wrapper::Wrapper)->rbatis::Result<()>"));
+        assert!(code.contains("Self::check_delete(executor,w).await"));
+
+        let config = read_config("after_update:notify");
+        let code = gen_hooks(&config.hooks).to_string().replace(' ', "");
+        assert!(code.contains("asyncfnafter_update<E>(&mutself,executor:&mutE)->rbatis::Result<()>"));
+        assert!(code.contains("self.notify(executor).await"));
+    }
+}
@@ -1,4 +1,5 @@
 pub mod crud_table_impl;
 pub mod py_sql_impl;
 pub mod sql_impl;
+pub mod wrapper_filter_impl;
 mod html_loader;
\ No newline at end of file
@@ -0,0 +1,135 @@
+use crate::proc_macro::TokenStream;
+use quote::quote;
+use syn;
+
+///impl WrapperFilter
+pub(crate) fn impl_wrapper_filter(ast: &syn::DeriveInput) -> TokenStream {
+    let name = &ast.ident;
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+    let mut items = quote! {};
+    match &ast.data {
+        syn::Data::Struct(s) => {
+            for field in &s.fields {
+                let ident = match &field.ident {
+                    None => {
+                        continue;
+                    }
+                    Some(v) => v,
+                };
+                let (op, column, skip) = parse_filter_attr(field);
+                if skip {
+                    continue;
+                }
+                let column = column.unwrap_or(ident.to_string().trim_start_matches("r#").to_string());
+                let method = gen_method(&op, &column);
+                if is_option(&field.ty) {
+                    items = quote! {
+                        #items
+                        if let Some(v) = &self.#ident {
+                            w = #method;
+                        }
+                    };
+                } else {
+                    items = quote! {
+                        #items
+                        {
+                            let v = &self.#ident;
+                            w = #method;
+                        }
+                    };
+                }
+            }
+        }
+        _ => {
+            panic!("[rbatis] #[derive(WrapperFilter)] only support struct!")
+        }
+    }
+    let gen = quote! {
+        impl #impl_generics rbatis::wrapper::WrapperFilter for #name #ty_generics #where_clause {
+            fn filter(&self, w: rbatis::wrapper::Wrapper) -> rbatis::wrapper::Wrapper {
+                let mut w = w;
+                #items
+                w
+            }
+        }
+    };
+    gen.into()
+}
+
+/// the wrapper method call of op,the field value is 'v'
+fn gen_method(op: &str, column: &str) -> proc_macro2::TokenStream {
+    match op {
+        "eq" => quote! { w.eq(#column, v) },
+        "ne" => quote! { w.ne(#column, v) },
+        "gt" => quote! { w.gt(#column, v) },
+        "ge" => quote! { w.ge(#column, v) },
+        "lt" => quote! { w.lt(#column, v) },
+        "le" => quote! { w.le(#column, v) },
+        "like" => quote! { w.like(#column, v) },
+        "like_left" => quote! { w.like_left(#column, v) },
+        "like_right" => quote! { w.like_right(#column, v) },
+        "not_like" => quote! { w.not_like(#column, v) },
+        "ilike" => quote! { w.ilike(#column, v) },
+        "in" => quote! { w.in_array(#column, &v[..]) },
+        "not_in" => quote! { w.not_in(#column, &v[..]) },
+        _ => {
+            panic!("[rbatis] #[filter] op:'{}' must be eq,ne,gt,ge,lt,le,like,like_left,like_right,not_like,ilike,in,not_in!", op)
+        }
+    }
+}
+
+/// parse #[filter(op = "like", column = "name")] or #[filter(skip)],return (op,column,skip)
+fn parse_filter_attr(field: &syn::Field) -> (String, Option<String>, bool) {
+    let mut op = "eq".to_string();
+    let mut column = None;
+    let mut skip = false;
+    for attr in &field.attrs {
+        if !attr.path.is_ident("filter") {
+            continue;
+        }
+        let list = match attr.parse_meta() {
+            Ok(syn::Meta::List(list)) => list,
+            _ => {
+                panic!("[rbatis] #[filter] must be #[filter(op = \"...\", column = \"...\")] or #[filter(skip)]!")
+            }
+        };
+        for item in list.nested {
+            match item {
+                syn::NestedMeta::Meta(syn::Meta::Path(path)) if path.is_ident("skip") => {
+                    skip = true;
+                }
+                syn::NestedMeta::Meta(syn::Meta::NameValue(nv)) => {
+                    let value = match &nv.lit {
+                        syn::Lit::Str(s) => s.value(),
+                        _ => {
+                            panic!("[rbatis] #[filter] value must be string,for example: op = \"like\"")
+                        }
+                    };
+                    if nv.path.is_ident("op") {
+                        op = value;
+                    } else if nv.path.is_ident("column") {
+                        column = Some(value);
+                    } else {
+                        panic!("[rbatis] #[filter] only support op,column and skip!")
+                    }
+                }
+                _ => {
+                    panic!("[rbatis] #[filter] only support op,column and skip!")
+                }
+            }
+        }
+    }
+    (op, column, skip)
+}
+
+fn is_option(ty: &syn::Type) -> bool {
+    match ty {
+        syn::Type::Path(p) => {
+            match p.path.segments.last() {
+                Some(seg) => seg.ident == "Option",
+                None => false,
+            }
+        }
+        _ => false,
+    }
+}
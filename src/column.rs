@@ -0,0 +1,142 @@
+use std::fmt::{Debug, Display, Formatter};
+use std::marker::PhantomData;
+use std::ops::Deref;
+
+/// Typed column of an table, T is the table type, V is the field type.
+/// #[crud_table] will gen column constants for every table, for example:
+///
+///  #[crud_table]
+///  pub struct BizActivity {
+///     pub id: Option<String>,
+///     pub name: Option<String>,
+///  }
+///  //BizActivity::COLUMNS.name is Column<BizActivity, Option<String>>
+///  let w = rb.new_wrapper()
+///            .eq(BizActivity::COLUMNS.name, "test")   // ok
+///            .eq(BizActivity::COLUMNS.name, 1)        // compile error,1 is not String
+///            .order_by(true, &[&BizActivity::COLUMNS.id]);
+pub struct Column<T, V> {
+    name: &'static str,
+    p: PhantomData<fn() -> (T, V)>,
+}
+
+impl<T, V> Column<T, V> {
+    pub const fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            p: PhantomData,
+        }
+    }
+
+    /// column name
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+impl<T, V> Clone for Column<T, V> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, V> Copy for Column<T, V> {}
+
+impl<T, V> Debug for Column<T, V> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Column").field(&self.name).finish()
+    }
+}
+
+impl<T, V> Display for Column<T, V> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name)
+    }
+}
+
+impl<T, V> Deref for Column<T, V> {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        self.name
+    }
+}
+
+impl<T, V> AsRef<str> for Column<T, V> {
+    fn as_ref(&self) -> &str {
+        self.name
+    }
+}
+
+/// column name arg of Wrapper, impl for &str,String and Column
+pub trait IntoColumn {
+    fn column_name(&self) -> &str;
+}
+
+/// column arg which can compare with value V,
+/// &str/String column accept any value,Column<T, C> only accept value impl ColumnValue<C>
+pub trait ColumnArg<V>: IntoColumn {}
+
+impl<'a> IntoColumn for &'a str {
+    fn column_name(&self) -> &str {
+        self
+    }
+}
+
+impl<'a, 'b> IntoColumn for &'a &'b str {
+    fn column_name(&self) -> &str {
+        self
+    }
+}
+
+impl IntoColumn for String {
+    fn column_name(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<'a> IntoColumn for &'a String {
+    fn column_name(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<T, C> IntoColumn for Column<T, C> {
+    fn column_name(&self) -> &str {
+        self.name
+    }
+}
+
+impl<'a, T, C> IntoColumn for &'a Column<T, C> {
+    fn column_name(&self) -> &str {
+        self.name
+    }
+}
+
+impl<'a, V> ColumnArg<V> for &'a str {}
+
+impl<'a, 'b, V> ColumnArg<V> for &'a &'b str {}
+
+impl<V> ColumnArg<V> for String {}
+
+impl<'a, V> ColumnArg<V> for &'a String {}
+
+impl<T, C, V> ColumnArg<V> for Column<T, C> where V: ColumnValue<C> {}
+
+impl<'a, T, C, V> ColumnArg<V> for &'a Column<T, C> where V: ColumnValue<C> {}
+
+/// value can compare with column type C
+/// for example: column type Option<String> accept Option<String>,String,&String,&str
+pub trait ColumnValue<C> {}
+
+impl<C> ColumnValue<C> for C {}
+
+impl<C> ColumnValue<Option<C>> for C {}
+
+impl<'a, C> ColumnValue<C> for &'a C {}
+
+impl<'a, C> ColumnValue<Option<C>> for &'a C {}
+
+impl<'a> ColumnValue<String> for &'a str {}
+
+impl<'a> ColumnValue<Option<String>> for &'a str {}
@@ -3,8 +3,13 @@ use std::fmt::Display;
 use std::hash::Hash;
 
 use async_trait::async_trait;
+use futures::future::BoxFuture;
+use futures::stream::{BoxStream, StreamExt};
+use futures::Future;
 use serde::de::DeserializeOwned;
 use serde::{Serialize, Serializer, Deserialize, Deserializer};
+use serde::de::Visitor;
+use serde::de::value::{MapDeserializer, SeqDeserializer};
 use serde_json::{Map, Value};
 
 use crate::core::convert::{ResultCodec, StmtConvert};
@@ -12,13 +17,17 @@ use crate::core::db::DBExecResult;
 use crate::core::db::DriverType;
 use crate::core::Error;
 use crate::core::Result;
-use crate::executor::{ExecutorMut, RBatisConnExecutor, RBatisTxExecutor};
+use crate::executor::{Executor, ExecutorMut, LocalTxExecutor, RBatisConnExecutor, RBatisTxExecutor};
+use crate::plugin::audit::{self, AuditPlugin, AuditRecord};
+use crate::plugin::intercept::SqlOperation;
 use crate::plugin::page::{IPageRequest, Page, IPage};
+#[cfg(feature = "tracing")]
+use crate::plugin::trace;
 use crate::plugin::version_lock::VersionLockPlugin;
 use crate::rbatis::Rbatis;
 use crate::sql::rule::SqlRule;
 use crate::utils::string_util::to_snake_name;
-use crate::wrapper::Wrapper;
+use crate::wrapper::{max_in_len, Wrapper};
 use std::marker::PhantomData;
 use std::ops::{Deref, DerefMut};
 use serde_json::value::Value::Null;
@@ -29,7 +38,11 @@ use serde_json::value::Value::Null;
 /// for example:
 ///  #[crud_table(table_name:"biz_activity"|table_columns:"id,name,version,delete_flag"|formats_pg:"id:{}::uuid")]
 ///
+/// the lifecycle hooks(before_insert,after_insert,before_update,after_update,before_delete,after_fetch) are called by CRUDMut,
+/// return Err will abort the operation. a manual impl of hooks need #[rbatis::async_trait],
+/// or point at the methods by #[crud_table(before_insert:"validate"|after_fetch:"decode")]
 ///
+#[async_trait]
 pub trait CRUDTable: Send + Sync + Serialize + DeserializeOwned {
     /// is enable use plugin
     fn is_use_plugin(plugin_name: &str) -> bool { true }
@@ -84,6 +97,59 @@ pub trait CRUDTable: Send + Sync + Serialize + DeserializeOwned {
         return format!("{}", fields);
     }
 
+    /// the sensitive columns,their args will be masked in sql log(see RedactPolicy)
+    /// If a macro is used, the method is overridden by the macro(fields mark by #[crud_table(sensitive)])
+    fn sensitive_columns() -> Vec<String> {
+        vec![]
+    }
+
+    /// called before save*,save* take the entity by ref,so return Some(changed entity) to save it instead(the argument is not changed)
+    async fn before_insert<E>(&self, executor: &mut E) -> Result<Option<Self>>
+        where
+            E: ExecutorMut + Send + ?Sized,
+    {
+        Ok(None)
+    }
+
+    /// called after save* success,on the saved entity
+    async fn after_insert<E>(&self, executor: &mut E) -> Result<()>
+        where
+            E: ExecutorMut + Send + ?Sized,
+    {
+        Ok(())
+    }
+
+    /// called before update_by_wrapper/update_by_column
+    async fn before_update<E>(&mut self, executor: &mut E) -> Result<()>
+        where
+            E: ExecutorMut + Send + ?Sized,
+    {
+        Ok(())
+    }
+
+    /// called after update_by_wrapper/update_by_column changed rows
+    async fn after_update<E>(&mut self, executor: &mut E) -> Result<()>
+        where
+            E: ExecutorMut + Send + ?Sized,
+    {
+        Ok(())
+    }
+
+    /// called before remove_by_wrapper/remove_by_column,there is no entity when remove,so it take the wrapper of rows
+    async fn before_delete<E>(executor: &mut E, w: &Wrapper) -> Result<()>
+        where
+            E: ExecutorMut + Send + ?Sized,
+    {
+        Ok(())
+    }
+
+    /// called on every entity of fetch_by_wrapper/fetch_list_by_wrapper/fetch_page_by_wrapper(and the methods use them)
+    async fn after_fetch<E>(&mut self, executor: &mut E) -> Result<()>
+        where
+            E: ExecutorMut + Send + ?Sized,
+    {
+        Ok(())
+    }
 
     ///format column
     fn do_format_column(driver_type: &DriverType, column: &str, data: &mut String) {
@@ -190,6 +256,7 @@ pub trait CRUDTable: Send + Sync + Serialize + DeserializeOwned {
     }
 }
 
+#[async_trait]
 impl<T> CRUDTable for Option<T>
     where
         T: CRUDTable,
@@ -202,6 +269,10 @@ impl<T> CRUDTable for Option<T>
         T::table_columns()
     }
 
+    fn sensitive_columns() -> Vec<String> {
+        T::sensitive_columns()
+    }
+
     fn formats(driver_type: &DriverType) -> HashMap<String, fn(arg: &str) -> String> {
         T::formats(driver_type)
     }
@@ -219,6 +290,63 @@ impl<T> CRUDTable for Option<T>
         }
         T::make_value_sql_arg(self.as_ref().unwrap(), db_type, index, skips)
     }
+
+    async fn before_insert<E>(&self, executor: &mut E) -> Result<Option<Self>>
+        where
+            E: ExecutorMut + Send + ?Sized,
+    {
+        match self {
+            Some(v) => Ok(v.before_insert(executor).await?.map(Some)),
+            None => Ok(None),
+        }
+    }
+
+    async fn after_insert<E>(&self, executor: &mut E) -> Result<()>
+        where
+            E: ExecutorMut + Send + ?Sized,
+    {
+        match self {
+            Some(v) => v.after_insert(executor).await,
+            None => Ok(()),
+        }
+    }
+
+    async fn before_update<E>(&mut self, executor: &mut E) -> Result<()>
+        where
+            E: ExecutorMut + Send + ?Sized,
+    {
+        match self {
+            Some(v) => v.before_update(executor).await,
+            None => Ok(()),
+        }
+    }
+
+    async fn after_update<E>(&mut self, executor: &mut E) -> Result<()>
+        where
+            E: ExecutorMut + Send + ?Sized,
+    {
+        match self {
+            Some(v) => v.after_update(executor).await,
+            None => Ok(()),
+        }
+    }
+
+    async fn before_delete<E>(executor: &mut E, w: &Wrapper) -> Result<()>
+        where
+            E: ExecutorMut + Send + ?Sized,
+    {
+        T::before_delete(executor, w).await
+    }
+
+    async fn after_fetch<E>(&mut self, executor: &mut E) -> Result<()>
+        where
+            E: ExecutorMut + Send + ?Sized,
+    {
+        match self {
+            Some(v) => v.after_fetch(executor).await,
+            None => Ok(()),
+        }
+    }
 }
 
 
@@ -324,6 +452,72 @@ pub trait CRUD {
         where
             T: CRUDTable;
 
+    /// fetch aggregate values by a wrapper,R is deserialized from columns named by Agg::alias()
+    /// for example:
+    ///     #[derive(Deserialize)]
+    ///     struct Stat { sum_amount: Option<f64>, max_create_time: Option<String> }
+    ///     let stat: Stat = rb.fetch_aggregate::<Order, _>(&w, &[Agg::Sum("amount"), Agg::Max("create_time")]).await?;
+    async fn fetch_aggregate<T, R>(&self, w: &Wrapper, aggs: &[Agg<'_>]) -> Result<R>
+        where
+            T: CRUDTable,
+            R: DeserializeOwned + Send + Sync;
+
+    /// fetch grouped aggregate values by a wrapper with group_by(),having()...
+    /// K is deserialized from the group by column(or an object of the columns if group by many columns),
+    /// V is deserialized from columns named by Agg::alias()
+    /// for example:
+    ///     let w = rb.new_wrapper().group_by(&["status"]).having("count(1) > 1");
+    ///     let stats: Vec<(i32, Stat)> = rb.fetch_group_aggregate::<Order, _, _>(&w, &[Agg::Sum("amount")]).await?;
+    async fn fetch_group_aggregate<T, K, V>(&self, w: &Wrapper, aggs: &[Agg<'_>]) -> Result<Vec<(K, V)>>
+        where
+            T: CRUDTable,
+            K: DeserializeOwned + Send + Sync,
+            V: DeserializeOwned + Send + Sync;
+
+    /// fetch sum(column) by a wrapper
+    async fn fetch_sum<T, R>(&self, w: &Wrapper, column: &str) -> Result<R>
+        where
+            T: CRUDTable,
+            R: DeserializeOwned + Send + Sync,
+    {
+        self.fetch_aggregate::<T, R>(w, &[Agg::Sum(column)]).await
+    }
+
+    /// fetch avg(column) by a wrapper
+    async fn fetch_avg<T, R>(&self, w: &Wrapper, column: &str) -> Result<R>
+        where
+            T: CRUDTable,
+            R: DeserializeOwned + Send + Sync,
+    {
+        self.fetch_aggregate::<T, R>(w, &[Agg::Avg(column)]).await
+    }
+
+    /// fetch max(column) by a wrapper
+    async fn fetch_max<T, R>(&self, w: &Wrapper, column: &str) -> Result<R>
+        where
+            T: CRUDTable,
+            R: DeserializeOwned + Send + Sync,
+    {
+        self.fetch_aggregate::<T, R>(w, &[Agg::Max(column)]).await
+    }
+
+    /// fetch min(column) by a wrapper
+    async fn fetch_min<T, R>(&self, w: &Wrapper, column: &str) -> Result<R>
+        where
+            T: CRUDTable,
+            R: DeserializeOwned + Send + Sync,
+    {
+        self.fetch_aggregate::<T, R>(w, &[Agg::Min(column)]).await
+    }
+
+    /// fetch count(distinct column) by a wrapper
+    async fn fetch_count_distinct<T>(&self, w: &Wrapper, column: &str) -> Result<u64>
+        where
+            T: CRUDTable,
+    {
+        self.fetch_aggregate::<T, u64>(w, &[Agg::CountDistinct(column)]).await
+    }
+
     /// fetch page database record list by a wrapper
     async fn fetch_page_by_wrapper<T>(
         &self,
@@ -348,6 +542,37 @@ pub trait CRUD {
         where
             T: CRUDTable;
 
+    /// fetch database record as a Stream by a wrapper on an new connection,see CRUDMut::fetch_stream_by_wrapper()
+    /// for example:
+    ///     let mut stream = rb.fetch_stream_by_wrapper::<BizActivity>(&rb.new_wrapper().eq("status", 1), 1000);
+    ///     while let Some(item) = stream.next().await {
+    ///         let item = item?;
+    ///     }
+    fn fetch_stream_by_wrapper<'s, T>(&'s self, w: &Wrapper, chunk_size: u64) -> BoxStream<'s, Result<T>>
+        where
+            T: CRUDTable + 's;
+
+    /// fetch joined record list by a wrapper with joins,T is the main table.
+    /// columns of each table are selected as 'alias__column',
+    /// and every row is deserialized as R from {"alias":{table columns},...} or [{main table columns},{join table columns},...],
+    /// so R can be an DTO with a field for each alias or a tuple (A, B) in join order.
+    /// all null table(for example,left join not match) is deserialized from null,use Option<B> for it.
+    /// for example:
+    ///     let w = rb.new_wrapper_table::<BizActivity>().alias("a")
+    ///                .left_join_as::<User>("u", "a.user_id = u.id")
+    ///                .eq("a.status", 1);
+    ///     let list: Vec<(BizActivity, Option<User>)> = rb.fetch_list_by_join::<BizActivity, _>(&w).await?;
+    async fn fetch_list_by_join<T, R>(&self, w: &Wrapper) -> Result<Vec<R>>
+        where
+            T: CRUDTable,
+            R: DeserializeOwned + Send + Sync;
+
+    /// fetch page joined record list by a wrapper with joins,see fetch_list_by_join()
+    async fn fetch_page_by_join<T, R>(&self, w: &Wrapper, page: &dyn IPageRequest) -> Result<Page<R>>
+        where
+            T: CRUDTable,
+            R: DeserializeOwned + Send + Sync;
+
     /// fetch page result(prepare sql)
     async fn fetch_page<T>(
         &self,
@@ -357,6 +582,56 @@ pub trait CRUD {
     ) -> Result<Page<T>>
         where
             T: DeserializeOwned + Serialize + Send + Sync;
+
+    /// fetch all record of the wrapper in chunks(order by "id"),and call f for each chunk.
+    /// use keyset iteration(id > last_id order by id limit chunk_size),not offset.
+    /// the wrapper must only have where conditions(no order by/group by/limit).
+    /// for example:
+    ///     rb.for_each_chunk::<BizActivity, _, _>(&rb.new_wrapper(), 100, |batch| async move {
+    ///         println!("{}", batch.len());
+    ///         Ok(())
+    ///     }).await?;
+    async fn for_each_chunk<T, F, Fut>(&self, w: &Wrapper, chunk_size: u64, f: F) -> Result<ChunkProgress>
+        where
+            T: CRUDTable,
+            F: FnMut(Vec<T>) -> Fut + Send,
+            Fut: Future<Output=Result<()>> + Send;
+
+    /// same as for_each_chunk(),but order by key_column and resume from progress.last_key.
+    /// progress is updated after each chunk is processed,so if f return an error,
+    /// progress.last_key is the last key which is successfully processed.
+    async fn for_each_chunk_by<T, F, Fut>(
+        &self,
+        w: &Wrapper,
+        key_column: &str,
+        chunk_size: u64,
+        progress: &mut ChunkProgress,
+        f: F,
+    ) -> Result<()>
+        where
+            T: CRUDTable,
+            F: FnMut(Vec<T>) -> Fut + Send,
+            Fut: Future<Output=Result<()>> + Send;
+
+    /// same as for_each_chunk_by(),but each chunk run in an transaction,
+    /// commit if f return Ok, rollback if f return Err.
+    /// for example:
+    ///     let mut progress = ChunkProgress::default();
+    ///     rb.for_each_chunk_tx::<BizActivity, _>(&rb.new_wrapper(), "id", 100, &mut progress, |batch, tx| Box::pin(async move {
+    ///         tx.save_batch(&batch, &[]).await?;
+    ///         Ok(())
+    ///     })).await?;
+    async fn for_each_chunk_tx<'s, T, F>(
+        &'s self,
+        w: &Wrapper,
+        key_column: &str,
+        chunk_size: u64,
+        progress: &mut ChunkProgress,
+        f: F,
+    ) -> Result<()>
+        where
+            T: CRUDTable,
+            F: for<'t> FnMut(Vec<T>, &'t mut RBatisTxExecutor<'s>) -> BoxFuture<'t, Result<()>> + Send + 's;
 }
 
 #[async_trait]
@@ -371,19 +646,36 @@ pub trait CRUDMut: ExecutorMut {
         where
             T: CRUDTable,
     {
-        if w.sql.starts_with(crate::sql::TEMPLATE.insert_into.value) {
-            return self.exec(&w.sql, &w.args).await;
+        if need_audit_tx::<T>(self.get_rbatis(), self.in_transaction()) {
+            let mut tx = self.begin_local().await?;
+            let result = tx.save_by_wrapper(table, w, skips).await;
+            return end_local_tx(tx, result).await;
+        }
+        let changed = table.before_insert(self).await?;
+        let table = changed.as_ref().unwrap_or(table);
+        let driver_type = self.driver_type()?;
+        let (sql, args) = w.build(&driver_type);
+        let result = if sql.starts_with(crate::sql::TEMPLATE.insert_into.value) {
+            self.exec_with(Some(&T::table_name()), &sql, &args).await?
         } else {
             let mut w = w.clone();
             let mut index = 0;
-            let (columns, column_values, args) = table.make_value_sql_arg(&self.driver_type()?, &mut index, skips)?;
+            let (columns, column_values, args) = table.make_value_sql_arg(&driver_type, &mut index, skips)?;
             let table_name = choose_dyn_table_name::<T>(&w);
             w = w.insert_into(&table_name, &columns, &column_values);
             for x in args {
-                w.args.push(x);
+                w = w.push_arg(x);
             }
-            return self.exec(&w.sql, &w.args).await;
+            let (sql, args) = w.build(&driver_type);
+            self.exec_with(Some(&T::table_name()), &sql, &args).await?
+        };
+        if let Some(plugin) = audit_plugin::<T>(self.get_rbatis()) {
+            let mut records = audit::make_insert_records(plugin, &choose_dyn_table_name::<T>(w), vec![json!(table)], result.last_insert_id);
+            audit::mask_records(&mut records, &T::sensitive_columns());
+            save_audit_records(self, records).await?;
         }
+        table.after_insert(self).await?;
+        return Ok(result);
     }
 
     /// save one entity to database
@@ -391,6 +683,13 @@ pub trait CRUDMut: ExecutorMut {
         where
             T: CRUDTable,
     {
+        if need_audit_tx::<T>(self.get_rbatis(), self.in_transaction()) {
+            let mut tx = self.begin_local().await?;
+            let result = tx.save(table, skips).await;
+            return end_local_tx(tx, result).await;
+        }
+        let changed = table.before_insert(self).await?;
+        let table = changed.as_ref().unwrap_or(table);
         let mut index = 0;
         let (columns, values, args) =
             table.make_value_sql_arg(&self.driver_type()?, &mut index, skips)?;
@@ -402,7 +701,14 @@ pub trait CRUDMut: ExecutorMut {
             crate::sql::TEMPLATE.values.value,
             values
         );
-        return self.exec(sql.as_str(), &args).await;
+        let result = self.exec_with(Some(&T::table_name()), sql.as_str(), &args).await?;
+        if let Some(plugin) = audit_plugin::<T>(self.get_rbatis()) {
+            let mut records = audit::make_insert_records(plugin, &T::table_name(), vec![json!(table)], result.last_insert_id);
+            audit::mask_records(&mut records, &T::sensitive_columns());
+            save_audit_records(self, records).await?;
+        }
+        table.after_insert(self).await?;
+        return Ok(result);
     }
 
     /// save batch makes many value into  only one sql. make sure your data do not too long!
@@ -416,17 +722,27 @@ pub trait CRUDMut: ExecutorMut {
         where
             T: CRUDTable,
     {
+        if need_audit_tx::<T>(self.get_rbatis(), self.in_transaction()) {
+            let mut tx = self.begin_local().await?;
+            let result = tx.save_batch(tables, skips).await;
+            return end_local_tx(tx, result).await;
+        }
         if tables.is_empty() {
             return Ok(DBExecResult {
                 rows_affected: 0,
                 last_insert_id: None,
             });
         }
+        let mut changed = Vec::with_capacity(tables.len());
+        for x in tables {
+            changed.push(x.before_insert(self).await?);
+        }
+        let tables: Vec<&T> = tables.iter().zip(&changed).map(|(x, c)| c.as_ref().unwrap_or(x)).collect();
         let mut value_arr = String::new();
         let mut arg_arr = vec![];
         let mut column_sql = String::new();
         let mut field_index = 0;
-        for x in tables {
+        for x in &tables {
             let (columns, values, args) =
                 x.make_value_sql_arg(&self.driver_type()?, &mut field_index, skips)?;
             if column_sql.is_empty() {
@@ -446,7 +762,17 @@ pub trait CRUDMut: ExecutorMut {
             crate::sql::TEMPLATE.values.value,
             value_arr
         );
-        return self.exec(sql.as_str(), &arg_arr).await;
+        let result = self.exec_with(Some(&T::table_name()), sql.as_str(), &arg_arr).await?;
+        if let Some(plugin) = audit_plugin::<T>(self.get_rbatis()) {
+            let rows = tables.iter().map(|x| json!(x)).collect();
+            let mut records = audit::make_insert_records(plugin, &T::table_name(), rows, result.last_insert_id);
+            audit::mask_records(&mut records, &T::sensitive_columns());
+            save_audit_records(self, records).await?;
+        }
+        for x in tables {
+            x.after_insert(self).await?;
+        }
+        return Ok(result);
     }
 
     /// save batch slice makes many value into  many sql. make sure your slice_len do not too long!
@@ -466,6 +792,11 @@ pub trait CRUDMut: ExecutorMut {
         where
             T: CRUDTable,
     {
+        if need_audit_tx::<T>(self.get_rbatis(), self.in_transaction()) {
+            let mut tx = self.begin_local().await?;
+            let result = tx.save_batch_slice(tables, slice_len, skips).await;
+            return end_local_tx(tx, result).await;
+        }
         if slice_len == 0 || tables.len() <= slice_len {
             return self.save_batch(tables, skips).await;
         } else {
@@ -497,8 +828,15 @@ pub trait CRUDMut: ExecutorMut {
         where
             T: CRUDTable,
     {
+        if need_audit_tx::<T>(self.get_rbatis(), self.in_transaction()) {
+            let mut tx = self.begin_local().await?;
+            let result = tx.remove_by_wrapper::<T>(w).await;
+            return end_local_tx(tx, result).await;
+        }
+        T::before_delete(self, w).await?;
         let table_name = choose_dyn_table_name::<T>(w);
-        let where_sql = self.driver_type()?.make_where(&w.sql);
+        let (where_sql, args) = w.build(&self.driver_type()?);
+        let where_sql = self.driver_type()?.make_where(&where_sql);
         let mut sql = String::new();
         if self.get_rbatis().logic_plugin.is_some() && T::is_use_plugin(self.get_rbatis().logic_plugin.as_ref().unwrap().name()) {
             sql = self.get_rbatis().logic_plugin.as_ref().unwrap().create_remove_sql(
@@ -515,10 +853,22 @@ pub trait CRUDMut: ExecutorMut {
                 &where_sql
             );
         }
-        return Ok(self
-            .exec(sql.as_str(), &w.args)
+        let olds = match audit_plugin::<T>(self.get_rbatis()) {
+            Some(_) => Some(fetch_audit_rows::<_, T>(self, w).await?),
+            None => None,
+        };
+        let rows_affected = self
+            .exec_with(Some(&T::table_name()), sql.as_str(), &args)
             .await?
-            .rows_affected);
+            .rows_affected;
+        if let (Some(olds), true) = (olds, rows_affected > 0) {
+            if let Some(plugin) = audit_plugin::<T>(self.get_rbatis()) {
+                let mut records = audit::make_delete_records(plugin, &table_name, olds);
+                audit::mask_records(&mut records, &T::sensitive_columns());
+                save_audit_records(self, records).await?;
+            }
+        }
+        return Ok(rows_affected);
     }
 
     /// remove database record by id
@@ -526,6 +876,13 @@ pub trait CRUDMut: ExecutorMut {
         where
             T: CRUDTable, C: Serialize + Send + Sync,
     {
+        if need_audit_tx::<T>(self.get_rbatis(), self.in_transaction()) {
+            let mut tx = self.begin_local().await?;
+            let result = tx.remove_by_column::<T, C>(column, value).await;
+            return end_local_tx(tx, result).await;
+        }
+        let w = self.get_rbatis().new_wrapper_table::<T>().eq(column, value);
+        T::before_delete(self, &w).await?;
         let mut sql = String::new();
         let driver_type = &self.driver_type()?;
         let mut data = String::new();
@@ -554,30 +911,64 @@ pub trait CRUDMut: ExecutorMut {
                 data
             );
         }
-        return Ok(self
-            .exec(&sql, &vec![json!(value)])
+        let olds = match audit_plugin::<T>(self.get_rbatis()) {
+            Some(_) => Some(fetch_audit_rows::<_, T>(self, &w).await?),
+            None => None,
+        };
+        let rows_affected = self
+            .exec_with(Some(&T::table_name()), &sql, &vec![json!(value)])
             .await?
-            .rows_affected);
+            .rows_affected;
+        if let (Some(olds), true) = (olds, rows_affected > 0) {
+            if let Some(plugin) = audit_plugin::<T>(self.get_rbatis()) {
+                let mut records = audit::make_delete_records(plugin, &T::table_name(), olds);
+                audit::mask_records(&mut records, &T::sensitive_columns());
+                save_audit_records(self, records).await?;
+            }
+        }
+        return Ok(rows_affected);
     }
 
     ///remove batch id
     /// for Example :
     /// rb.remove_batch_by_column::<BizActivity>(&["1".to_string(),"2".to_string()]).await;
     /// [rbatis] Exec ==> delete from biz_activity where id IN ( ? , ? )
+    /// the values over the param limit of driver are removed by chunks in one transaction of this connection
     ///
     async fn remove_batch_by_column<T, C>(&mut self, column: &str, values: &[C]) -> Result<u64>
         where
             T: CRUDTable, C: Serialize + Send + Sync
     {
+        if need_audit_tx::<T>(self.get_rbatis(), self.in_transaction()) {
+            let mut tx = self.begin_local().await?;
+            let result = tx.remove_batch_by_column::<T, C>(column, values).await;
+            return end_local_tx(tx, result).await;
+        }
         if values.is_empty() {
             return Ok(0);
         }
-        let w = self
-            .get_rbatis()
-            .new_wrapper_table::<T>()
-            .and()
-            .in_array(column, values);
-        return self.remove_by_wrapper::<T>(&w).await;
+        //split the values,one sql can not over the param limit of driver
+        let driver_type = self.driver_type()?;
+        let ws: Vec<Wrapper> = values
+            .chunks(max_in_len(&driver_type))
+            .map(|chunk| self.get_rbatis().new_wrapper_table::<T>().and().in_array(column, chunk))
+            .collect();
+        let mut rows_affected = 0;
+        if ws.len() == 1 || self.in_transaction() {
+            for w in &ws {
+                rows_affected += self.remove_by_wrapper::<T>(w).await?;
+            }
+            return Ok(rows_affected);
+        }
+        //remove all chunks in an transaction of this connection
+        let mut tx = self.begin_local().await?;
+        for w in &ws {
+            match tx.remove_by_wrapper::<T>(w).await {
+                Ok(v) => rows_affected += v,
+                Err(e) => return end_local_tx(tx, Err(e)).await,
+            }
+        }
+        return end_local_tx(tx, Ok(rows_affected)).await;
     }
 
     /// update_by_wrapper
@@ -591,138 +982,13 @@ pub trait CRUDMut: ExecutorMut {
         where
             T: CRUDTable,
     {
-        let table_name = choose_dyn_table_name::<T>(w);
-        let mut args = vec![];
-        let mut old_version = serde_json::Value::Null;
-        let driver_type = &self.driver_type()?;
-        let columns = T::table_columns();
-        let columns_vec: Vec<&str> = columns.split(",").collect();
-        let map;
-        match serde_json::json!(table) {
-            serde_json::Value::Object(m) => {
-                map = m;
-            }
-            _ => {
-                return Err(Error::from("[rbatis] arg not an json object!"));
-            }
-        }
-        let null = serde_json::Value::Null;
-        let mut sets = String::new();
-
-        for column in columns_vec {
-            //filter
-            let mut is_continue = false;
-            for x in skips {
-                match x {
-                    Skip::Column(skip_column) => {
-                        if skip_column.eq(&column) {
-                            is_continue = true;
-                            break;
-                        }
-                    }
-                    _ => {}
-                }
-            }
-            if is_continue {
-                continue;
-            }
-            let mut v = map.get(column).unwrap_or_else(|| &null).clone();
-            //filter null
-            let is_null = v.is_null();
-            for x in skips {
-                match x {
-                    Skip::Value(skip_value) => {
-                        if (*skip_value).eq(&v) {
-                            is_continue = true;
-                            break;
-                        }
-                    }
-                    _ => {}
-                }
-            }
-            if is_continue {
-                continue;
-            }
-            let mut data = String::new();
-            driver_type.stmt_convert(args.len(), &mut data);
-            T::do_format_column(
-                &driver_type,
-                &column,
-                &mut data,
-            );
-            sets.push_str(
-                format!(
-                    " {} = {},",
-                    column,
-                    data
-                ).as_str(),
-            );
-            match &self.get_rbatis().version_lock_plugin {
-                Some(version_lock_plugin) => {
-                    if T::is_use_plugin(self.get_rbatis().version_lock_plugin.as_ref().unwrap().name()) {
-                        old_version = v.clone();
-                        v = version_lock_plugin.try_add_one(&old_version, column);
-                    }
-                }
-                _ => {}
-            }
-            args.push(v.clone());
-        }
-        sets.pop();
-        let mut wrapper = self.get_rbatis().new_wrapper_table::<T>();
-        wrapper.sql = format!(
-            "{} {} {} {} ",
-            crate::sql::TEMPLATE.update.value,
-            table_name,
-            crate::sql::TEMPLATE.set.value,
-            sets
-        );
-        wrapper.args = args;
-
-
-        //version lock
-        match self.get_rbatis().version_lock_plugin.as_ref() {
-            Some(version_lock_plugin) => {
-                if T::is_use_plugin(self.get_rbatis().version_lock_plugin.as_ref().unwrap().name()) {
-                    let version_sql = version_lock_plugin
-                        .as_ref()
-                        .try_make_where_sql(&old_version);
-                    if !version_sql.is_empty() {
-                        if !wrapper
-                            .sql
-                            .contains(crate::sql::TEMPLATE.r#where.left_right_space)
-                        {
-                            wrapper
-                                .sql
-                                .push_str(crate::sql::TEMPLATE.r#where.left_right_space);
-                        }
-                        wrapper.sql.push_str(&version_sql);
-                    }
-                }
-            }
-            _ => {}
-        }
-        if !w.sql.is_empty() {
-            if !wrapper
-                .sql
-                .contains(crate::sql::TEMPLATE.r#where.left_right_space)
-            {
-                wrapper
-                    .sql
-                    .push_str(crate::sql::TEMPLATE.r#where.left_right_space);
-            }
-            wrapper = wrapper.and();
-            wrapper = wrapper.push_wrapper(&w);
-        }
-
-        let rows_affected = self
-            .exec(wrapper.sql.as_str(), &wrapper.args)
-            .await?
-            .rows_affected;
-        if rows_affected > 0 {
-            *table = serde_json::from_value(serde_json::Value::Object(map)).into_result()?;
+        if need_audit_tx::<T>(self.get_rbatis(), self.in_transaction()) {
+            let mut tx = self.begin_local().await?;
+            let result = tx.update_by_wrapper(table, w, skips).await;
+            return end_local_tx(tx, result).await;
         }
-        return Ok(rows_affected);
+        table.before_update(self).await?;
+        update_table(self, table, w, skips).await
     }
 
     /// update database record by id
@@ -731,17 +997,16 @@ pub trait CRUDMut: ExecutorMut {
         where
             T: CRUDTable
     {
-        let rb = self
-            .get_rbatis();
+        if need_audit_tx::<T>(self.get_rbatis(), self.in_transaction()) {
+            let mut tx = self.begin_local().await?;
+            let result = tx.update_by_column(column, table).await;
+            return end_local_tx(tx, result).await;
+        }
+        //the hook may change the column,so read it after the hook
+        table.before_update(self).await?;
         let value = table.get(column);
-        self.update_by_wrapper(
-            table,
-            &rb
-                .new_wrapper_table::<T>()
-                .eq(column, value),
-            &[Skip::Value(Value::Null), Skip::Column("id"), Skip::Column(column)],
-        )
-            .await
+        let w = self.get_rbatis().new_wrapper_table::<T>().eq(column, value);
+        update_table(self, table, &w, &[Skip::Value(Value::Null), Skip::Column("id"), Skip::Column(column)]).await
     }
 
     /// remove batch database record by args
@@ -749,6 +1014,11 @@ pub trait CRUDMut: ExecutorMut {
         where
             T: CRUDTable
     {
+        if need_audit_tx::<T>(self.get_rbatis(), self.in_transaction()) {
+            let mut tx = self.begin_local().await?;
+            let result = tx.update_batch_by_column(column, args).await;
+            return end_local_tx(tx, result).await;
+        }
         let mut updates = 0;
         for x in args {
             updates += self.update_by_column(column, x).await?
@@ -761,8 +1031,10 @@ pub trait CRUDMut: ExecutorMut {
         where
             T: CRUDTable,
     {
-        let sql = make_select_sql::<T>(self.get_rbatis(), &T::table_columns(), &w)?;
-        return self.fetch(sql.as_str(), &w.args).await;
+        let (sql, args) = make_select_sql::<T>(&self.driver_type()?, &make_table_columns::<T>(w), &w)?;
+        let mut result: T = self.fetch_with(SqlOperation::Fetch, Some(&T::table_name()), sql.as_str(), &args).await?;
+        result.after_fetch(self).await?;
+        return Ok(result);
     }
 
     /// count database record by a wrapper
@@ -770,111 +1042,287 @@ pub trait CRUDMut: ExecutorMut {
         where
             T: CRUDTable,
     {
-        let sql = make_select_sql::<T>(self.get_rbatis(), "count(1)", &w)?;
-        return self.fetch(sql.as_str(), &w.args).await;
+        let (sql, args) = make_select_sql::<T>(&self.driver_type()?, "count(1)", &w)?;
+        return self.fetch_with(SqlOperation::Aggregate, Some(&T::table_name()), sql.as_str(), &args).await;
     }
 
-    /// fetch database record by value
-    async fn fetch_by_column<T, C>(&mut self, column: &str, value: &C) -> Result<T>
+    /// fetch aggregate values by a wrapper,see CRUD::fetch_aggregate()
+    async fn fetch_aggregate<T, R>(&mut self, w: &Wrapper, aggs: &[Agg<'_>]) -> Result<R>
         where
-            T: CRUDTable, C: Serialize + Send + Sync,
+            T: CRUDTable,
+            R: DeserializeOwned + Send + Sync,
     {
-        let w = self.get_rbatis().new_wrapper_table::<T>().eq(&column, value);
-        return self.fetch_by_wrapper(&w).await;
+        if aggs.is_empty() {
+            return Err(Error::from("[rbatis] aggs can not be empty!"));
+        }
+        let columns = aggs.iter().map(|x| x.to_sql()).collect::<Vec<String>>().join(",");
+        let (sql, args) = make_select_sql::<T>(&self.driver_type()?, &columns, &w)?;
+        return self.fetch_with(SqlOperation::Aggregate, Some(&T::table_name()), sql.as_str(), &args).await;
     }
 
-    /// fetch database record list by a wrapper
-    async fn fetch_list_by_wrapper<T>(&mut self, w: &Wrapper) -> Result<Vec<T>>
+    /// fetch grouped aggregate values by a wrapper,see CRUD::fetch_group_aggregate()
+    async fn fetch_group_aggregate<T, K, V>(&mut self, w: &Wrapper, aggs: &[Agg<'_>]) -> Result<Vec<(K, V)>>
         where
             T: CRUDTable,
+            K: DeserializeOwned + Send + Sync,
+            V: DeserializeOwned + Send + Sync,
     {
-        let sql = make_select_sql::<T>(self.get_rbatis(), &T::table_columns(), &w)?;
-        return self.fetch(sql.as_str(), &w.args).await;
+        if w.group_by_columns().is_empty() {
+            return Err(Error::from("[rbatis] fetch_group_aggregate() wrapper must have group_by()!"));
+        }
+        if aggs.is_empty() {
+            return Err(Error::from("[rbatis] aggs can not be empty!"));
+        }
+        let mut columns = w.group_by_columns().join(",");
+        for x in aggs {
+            columns.push_str(",");
+            columns.push_str(&x.to_sql());
+        }
+        let (sql, args) = make_select_sql::<T>(&self.driver_type()?, &columns, &w)?;
+        let rows: Vec<Map<String, Value>> = self.fetch_with(SqlOperation::Aggregate, Some(&T::table_name()), sql.as_str(), &args).await?;
+        let mut results = Vec::with_capacity(rows.len());
+        for mut row in rows {
+            let mut key = Map::new();
+            for x in w.group_by_columns() {
+                //"a.status" => "status"
+                let name = x.rsplit('.').next().unwrap_or(x).trim();
+                let name = crate::utils::string_util::un_packing_string(name);
+                key.insert(name.to_string(), row.remove(name).unwrap_or(Value::Null));
+            }
+            let key = if key.len() == 1 {
+                key.into_iter().next().map(|(_, v)| v).unwrap_or(Value::Null)
+            } else {
+                Value::Object(key)
+            };
+            let mut values = Map::new();
+            for x in aggs {
+                let alias = x.alias();
+                let v = row.remove(&alias).unwrap_or(Value::Null);
+                values.insert(alias, v);
+            }
+            results.push((
+                serde_json::from_value(key).into_result()?,
+                serde_json::from_value(Value::Object(values)).into_result()?,
+            ));
+        }
+        Ok(results)
     }
 
-    /// fetch database record list for all
-    async fn fetch_list<T>(&mut self) -> Result<Vec<T>>
+    /// fetch sum(column) by a wrapper
+    async fn fetch_sum<T, R>(&mut self, w: &Wrapper, column: &str) -> Result<R>
         where
             T: CRUDTable,
+            R: DeserializeOwned + Send + Sync,
     {
-        let rb = self.get_rbatis();
-        return self.fetch_list_by_wrapper(&rb.new_wrapper_table::<T>())
-            .await;
+        self.fetch_aggregate::<T, R>(w, &[Agg::Sum(column)]).await
     }
 
-    /// fetch database record list by a id array
-    async fn fetch_list_by_column<T, C>(&mut self, column: &str, column_values: &[C]) -> Result<Vec<T>>
+    /// fetch avg(column) by a wrapper
+    async fn fetch_avg<T, R>(&mut self, w: &Wrapper, column: &str) -> Result<R>
         where
-            T: CRUDTable, C: Serialize + Send + Sync,
+            T: CRUDTable,
+            R: DeserializeOwned + Send + Sync,
     {
-        if column_values.is_empty() {
-            return Ok(vec![]);
-        }
-        let w = self.get_rbatis().new_wrapper_table::<T>().in_array(&column, column_values);
-        return self.fetch_list_by_wrapper(&w).await;
+        self.fetch_aggregate::<T, R>(w, &[Agg::Avg(column)]).await
     }
 
-    /// fetch page database record list by a wrapper
-    async fn fetch_page_by_wrapper<T>(
-        &mut self,
-        w: &Wrapper,
-        page: &dyn IPageRequest,
-    ) -> Result<Page<T>>
+    /// fetch max(column) by a wrapper
+    async fn fetch_max<T, R>(&mut self, w: &Wrapper, column: &str) -> Result<R>
         where
             T: CRUDTable,
+            R: DeserializeOwned + Send + Sync,
     {
-        let sql = make_select_sql::<T>(self.get_rbatis(), &T::table_columns(), &w)?;
-        self.fetch_page(sql.as_str(), &w.args, page).await
+        self.fetch_aggregate::<T, R>(w, &[Agg::Max(column)]).await
     }
 
-    /// fetch page result(prepare sql)
-    async fn fetch_page<T>(
-        &mut self,
-        sql: &str,
-        args: &Vec<serde_json::Value>,
-        page_request: &dyn IPageRequest,
-    ) -> Result<Page<T>>
+    /// fetch min(column) by a wrapper
+    async fn fetch_min<T, R>(&mut self, w: &Wrapper, column: &str) -> Result<R>
         where
-            T: DeserializeOwned + Serialize + Send + Sync,
+            T: CRUDTable,
+            R: DeserializeOwned + Send + Sync,
     {
-        let mut page_result = Page::new(page_request.get_page_no(), page_request.get_page_size());
-        page_result.search_count = page_request.is_search_count();
-        let (count_sql, sql) = self.get_rbatis().page_plugin.make_page_sql(
-            &self.driver_type()?,
-            &sql,
-            args,
-            page_request,
-        )?;
-        if page_request.is_search_count() {
-            //make count sql
-            let total: Option<u64> = self
-                .fetch(&count_sql, args)
-                .await?;
-            page_result.set_total(total.unwrap_or(0));
-            page_result.pages = page_result.get_pages();
-            if page_result.get_total() == 0 {
-                return Ok(page_result);
-            }
-        }
-        let data: Option<Vec<T>> = self.fetch(sql.as_str(), args).await?;
-        page_result.set_records(data.unwrap_or(vec![]));
-        page_result.pages = page_result.get_pages();
-        return Ok(page_result);
+        self.fetch_aggregate::<T, R>(w, &[Agg::Min(column)]).await
     }
-}
-
-
-impl CRUDMut for RBatisConnExecutor<'_> {}
 
-impl CRUDMut for RBatisTxExecutor<'_> {}
+    /// fetch count(distinct column) by a wrapper
+    async fn fetch_count_distinct<T>(&mut self, w: &Wrapper, column: &str) -> Result<u64>
+        where
+            T: CRUDTable,
+    {
+        self.fetch_aggregate::<T, u64>(w, &[Agg::CountDistinct(column)]).await
+    }
 
-/// choose table name
-fn choose_dyn_table_name<T>(w: &Wrapper) -> String
-    where
+    /// fetch database record by value
+    async fn fetch_by_column<T, C>(&mut self, column: &str, value: &C) -> Result<T>
+        where
+            T: CRUDTable, C: Serialize + Send + Sync,
+    {
+        let w = self.get_rbatis().new_wrapper_table::<T>().eq(&column, value);
+        return self.fetch_by_wrapper(&w).await;
+    }
+
+    /// fetch database record list by a wrapper
+    async fn fetch_list_by_wrapper<T>(&mut self, w: &Wrapper) -> Result<Vec<T>>
+        where
+            T: CRUDTable,
+    {
+        let (sql, args) = make_select_sql::<T>(&self.driver_type()?, &make_table_columns::<T>(w), &w)?;
+        let mut result: Vec<T> = self.fetch_with(SqlOperation::Fetch, Some(&T::table_name()), sql.as_str(), &args).await?;
+        for x in &mut result {
+            x.after_fetch(self).await?;
+        }
+        return Ok(result);
+    }
+
+    /// fetch database record as a Stream by a wrapper,only one chunk of rows is held in memory.
+    /// rbatis-core can not read rows from a cursor,so rows are fetched by keyset chunks(id > last_id order by id limit chunk_size),
+    /// not by offset,rows inserted or removed while streaming will not make other rows duplicated or skipped.
+    /// every chunk run the intercepts,log and after_fetch() hooks,the wrapper must only have where conditions(see for_each_chunk()).
+    /// for example:
+    ///     let mut stream = conn.fetch_stream_by_wrapper::<BizActivity>(&rb.new_wrapper(), 1000);
+    ///     while let Some(item) = stream.next().await {
+    ///         let item = item?;
+    ///     }
+    fn fetch_stream_by_wrapper<'s, T>(&'s mut self, w: &Wrapper, chunk_size: u64) -> BoxStream<'s, Result<T>>
+        where
+            T: CRUDTable + 's,
+            Self: Send + Sized,
+    {
+        make_chunk_stream(self, w, chunk_size)
+    }
+
+    /// fetch joined record list by a wrapper with joins,see CRUD::fetch_list_by_join()
+    async fn fetch_list_by_join<T, R>(&mut self, w: &Wrapper) -> Result<Vec<R>>
+        where
+            T: CRUDTable,
+            R: DeserializeOwned + Send + Sync,
+    {
+        let (sql, args) = make_select_sql::<T>(&self.driver_type()?, &make_join_columns::<T>(w), &w)?;
+        let rows: Vec<Map<String, Value>> = self.fetch_with(SqlOperation::Fetch, Some(&T::table_name()), sql.as_str(), &args).await?;
+        let mut results = Vec::with_capacity(rows.len());
+        for row in rows {
+            results.push(R::deserialize(make_join_row::<T>(w, row)).into_result()?);
+        }
+        Ok(results)
+    }
+
+    /// fetch page joined record list by a wrapper with joins,see CRUD::fetch_list_by_join()
+    async fn fetch_page_by_join<T, R>(&mut self, w: &Wrapper, page: &dyn IPageRequest) -> Result<Page<R>>
+        where
+            T: CRUDTable,
+            R: DeserializeOwned + Send + Sync,
+    {
+        let (sql, args) = make_select_sql::<T>(&self.driver_type()?, &make_join_columns::<T>(w), &w)?;
+        let page: Page<Map<String, Value>> = self.fetch_page(sql.as_str(), &args, page).await?;
+        page.try_map(|row| R::deserialize(make_join_row::<T>(w, row)).into_result())
+    }
+
+    /// fetch database record list for all
+    async fn fetch_list<T>(&mut self) -> Result<Vec<T>>
+        where
+            T: CRUDTable,
+    {
+        let rb = self.get_rbatis();
+        return self.fetch_list_by_wrapper(&rb.new_wrapper_table::<T>())
+            .await;
+    }
+
+    /// fetch database record list by a id array
+    async fn fetch_list_by_column<T, C>(&mut self, column: &str, column_values: &[C]) -> Result<Vec<T>>
+        where
+            T: CRUDTable, C: Serialize + Send + Sync,
+    {
+        if column_values.is_empty() {
+            return Ok(vec![]);
+        }
+        let mut result = vec![];
+        //split the values,one sql can not over the param limit of driver
+        for chunk in column_values.chunks(max_in_len(&self.driver_type()?)) {
+            let w = self.get_rbatis().new_wrapper_table::<T>().in_array(&column, chunk);
+            result.extend(self.fetch_list_by_wrapper::<T>(&w).await?);
+        }
+        return Ok(result);
+    }
+
+    /// fetch page database record list by a wrapper
+    async fn fetch_page_by_wrapper<T>(
+        &mut self,
+        w: &Wrapper,
+        page: &dyn IPageRequest,
+    ) -> Result<Page<T>>
+        where
+            T: CRUDTable,
+    {
+        let (sql, args) = make_select_sql::<T>(&self.driver_type()?, &make_table_columns::<T>(w), &w)?;
+        let mut result: Page<T> = self.fetch_page(sql.as_str(), &args, page).await?;
+        for x in &mut result.records {
+            x.after_fetch(self).await?;
+        }
+        Ok(result)
+    }
+
+    /// fetch page result(prepare sql)
+    async fn fetch_page<T>(
+        &mut self,
+        sql: &str,
+        args: &Vec<serde_json::Value>,
+        page_request: &dyn IPageRequest,
+    ) -> Result<Page<T>>
+        where
+            T: DeserializeOwned + Serialize + Send + Sync,
+    {
+        #[cfg(feature = "tracing")]
+        let span = trace::make_page_span(self.trace_parent(), &self.driver_type()?);
+        let future = async {
+            let mut page_result = Page::new(page_request.get_page_no(), page_request.get_page_size());
+            page_result.search_count = page_request.is_search_count();
+            let (count_sql, sql) = self.get_rbatis().page_plugin.make_page_sql(
+                &self.driver_type()?,
+                &sql,
+                args,
+                page_request,
+            )?;
+            if page_request.is_search_count() {
+                //make count sql
+                let total: Option<u64> = self
+                    .fetch_with(SqlOperation::PageCount, None, &count_sql, args)
+                    .await?;
+                page_result.set_total(total.unwrap_or(0));
+                page_result.pages = page_result.get_pages();
+                if page_result.get_total() == 0 {
+                    return Ok(page_result);
+                }
+            }
+            let data: Option<Vec<T>> = self.fetch(sql.as_str(), args).await?;
+            page_result.set_records(data.unwrap_or(vec![]));
+            page_result.pages = page_result.get_pages();
+            return Ok(page_result);
+        };
+        #[cfg(feature = "tracing")]
+        {
+            let result = tracing::Instrument::instrument(future, span.clone()).await;
+            trace::record_page(&span, &result);
+            return result;
+        }
+        #[cfg(not(feature = "tracing"))]
+        return future.await;
+    }
+}
+
+
+impl CRUDMut for RBatisConnExecutor<'_> {}
+
+impl CRUDMut for RBatisTxExecutor<'_> {}
+
+impl CRUDMut for LocalTxExecutor<'_> {}
+
+/// choose table name
+fn choose_dyn_table_name<T>(w: &Wrapper) -> String
+    where
         T: CRUDTable,
 {
     let mut table_name = T::table_name();
-    let table_name_format = w.formats.get("table_name");
+    let table_name_format = w.formats().get("table_name");
     if table_name_format.is_some() {
         match table_name_format {
             Some(table_name_format) => {
@@ -886,84 +1334,558 @@ fn choose_dyn_table_name<T>(w: &Wrapper) -> String
     return table_name;
 }
 
-fn make_select_sql<T>(rb: &Rbatis, column: &str, w: &Wrapper) -> Result<String>
+/// make the wrapper of next chunk: ([w]) and key_column > last_key order by key_column asc limit chunk_size
+pub fn make_chunk_wrapper(w: &Wrapper, key_column: &str, last_key: &Option<Value>, chunk_size: u64) -> Result<Wrapper> {
+    if chunk_size == 0 {
+        return Err(Error::from("[rbatis] chunk_size must be > 0!"));
+    }
+    if !w.order_by_columns().is_empty()
+        || !w.group_by_columns().is_empty()
+        || w.limit_size().is_some()
+        || !w.tail().is_empty() {
+        return Err(Error::from("[rbatis] chunk wrapper can not have 'order by','group by' or 'limit'!"));
+    }
+    //group the conditions of w,or the key bound will not apply to the 'or' branch
+    let mut w = w.clone().group();
+    if let Some(last_key) = last_key {
+        w = w.and().gt(key_column, last_key);
+    }
+    w = w.order_by(true, &[key_column]).limit(chunk_size);
+    Ok(w)
+}
+
+/// fetch next chunk, return None if no more rows
+async fn fetch_chunk<E, T>(executor: &mut E, w: &Wrapper, key_column: &str, chunk_size: u64, progress: &ChunkProgress) -> Result<Option<(Vec<T>, Value)>>
+    where
+        E: CRUDMut + Send,
+        T: CRUDTable,
+{
+    let chunk_w = make_chunk_wrapper(w, key_column, &progress.last_key, chunk_size)?;
+    let batch: Vec<T> = executor.fetch_list_by_wrapper(&chunk_w).await?;
+    match batch.last() {
+        None => Ok(None),
+        Some(last) => {
+            let last_key = last.get(key_column);
+            if last_key.is_null() {
+                return Err(Error::from(format!("[rbatis] chunk key column '{}' can not be null!", key_column)));
+            }
+            Ok(Some((batch, last_key)))
+        }
+    }
+}
+
+/// the state of fetch_stream_by_wrapper()
+struct ChunkStream<E, T> {
+    executor: E,
+    w: Wrapper,
+    chunk_size: u64,
+    progress: ChunkProgress,
+    rows: VecDeque<T>,
+    done: bool,
+}
+
+/// make a keyset chunk Stream on an executor(&mut E or Box<RBatisConnExecutor>),see CRUDMut::fetch_stream_by_wrapper()
+fn make_chunk_stream<'s, E, T>(executor: E, w: &Wrapper, chunk_size: u64) -> BoxStream<'s, Result<T>>
+    where
+        E: DerefMut + Send + 's,
+        E::Target: CRUDMut + Send + Sized,
+        T: CRUDTable + 's,
+{
+    //check the wrapper before the first chunk
+    if let Err(e) = make_chunk_wrapper(w, "id", &None, chunk_size) {
+        return futures::stream::once(futures::future::ready(Err(e))).boxed();
+    }
+    let state = ChunkStream {
+        executor: executor,
+        w: w.clone(),
+        chunk_size: chunk_size,
+        progress: ChunkProgress::default(),
+        rows: VecDeque::new(),
+        done: false,
+    };
+    futures::stream::unfold(state, |mut state| async move {
+        loop {
+            if let Some(row) = state.rows.pop_front() {
+                return Some((Ok(row), state));
+            }
+            if state.done {
+                return None;
+            }
+            let chunk = fetch_chunk::<_, T>(state.executor.deref_mut(), &state.w, "id", state.chunk_size, &state.progress).await;
+            match chunk {
+                Ok(Some((batch, last_key))) => {
+                    let len = batch.len() as u64;
+                    state.done = len < state.chunk_size;
+                    state.progress.done_chunk(last_key, len);
+                    state.rows = batch.into();
+                }
+                Ok(None) => {
+                    return None;
+                }
+                Err(e) => {
+                    state.done = true;
+                    return Some((Err(e), state));
+                }
+            }
+        }
+    }).boxed()
+}
+
+/// the audit plugin,None if not set or T not use it
+fn audit_plugin<T>(rb: &Rbatis) -> Option<&dyn AuditPlugin>
+    where
+        T: CRUDTable,
+{
+    rb.audit_plugin.as_deref().filter(|x| T::is_use_plugin(x.name()))
+}
+
+/// the update sql of update_by_wrapper,before_update is already called
+async fn update_table<E, T>(executor: &mut E, table: &mut T, w: &Wrapper, skips: &[Skip<'_>]) -> Result<u64>
+    where
+        E: ExecutorMut + Send + ?Sized,
+        T: CRUDTable,
+{
+    let table_name = choose_dyn_table_name::<T>(w);
+    let mut args = vec![];
+    let mut old_version = serde_json::Value::Null;
+    let driver_type = &executor.driver_type()?;
+    let columns = T::table_columns();
+    let columns_vec: Vec<&str> = columns.split(",").collect();
+    let map;
+    match serde_json::json!(table) {
+        serde_json::Value::Object(m) => {
+            map = m;
+        }
+        _ => {
+            return Err(Error::from("[rbatis] arg not an json object!"));
+        }
+    }
+    let null = serde_json::Value::Null;
+    let mut sets = String::new();
+    //the new values of sets,for audit
+    let mut set_values = Map::new();
+
+    for column in columns_vec {
+        //filter
+        let mut is_continue = false;
+        for x in skips {
+            match x {
+                Skip::Column(skip_column) => {
+                    if skip_column.eq(&column) {
+                        is_continue = true;
+                        break;
+                    }
+                }
+                _ => {}
+            }
+        }
+        if is_continue {
+            continue;
+        }
+        let mut v = map.get(column).unwrap_or_else(|| &null).clone();
+        //filter null
+        let is_null = v.is_null();
+        for x in skips {
+            match x {
+                Skip::Value(skip_value) => {
+                    if (*skip_value).eq(&v) {
+                        is_continue = true;
+                        break;
+                    }
+                }
+                _ => {}
+            }
+        }
+        if is_continue {
+            continue;
+        }
+        let mut data = String::new();
+        driver_type.stmt_convert(args.len(), &mut data);
+        T::do_format_column(
+            &driver_type,
+            &column,
+            &mut data,
+        );
+        sets.push_str(
+            format!(
+                " {} = {},",
+                column,
+                data
+            ).as_str(),
+        );
+        match &executor.get_rbatis().version_lock_plugin {
+            Some(version_lock_plugin) => {
+                if T::is_use_plugin(executor.get_rbatis().version_lock_plugin.as_ref().unwrap().name()) {
+                    old_version = v.clone();
+                    v = version_lock_plugin.try_add_one(&old_version, column);
+                }
+            }
+            _ => {}
+        }
+        set_values.insert(column.to_string(), v.clone());
+        args.push(v.clone());
+    }
+    sets.pop();
+    let mut wrapper = executor.get_rbatis().new_wrapper_table::<T>();
+    wrapper = wrapper.push(&format!(
+        "{} {} {} {} ",
+        crate::sql::TEMPLATE.update.value,
+        table_name,
+        crate::sql::TEMPLATE.set.value,
+        sets
+    ), &args);
+    let mut has_where = false;
+
+
+    //version lock
+    match executor.get_rbatis().version_lock_plugin.as_ref() {
+        Some(version_lock_plugin) => {
+            if T::is_use_plugin(executor.get_rbatis().version_lock_plugin.as_ref().unwrap().name()) {
+                let version_sql = version_lock_plugin
+                    .as_ref()
+                    .try_make_where_sql(&old_version);
+                if !version_sql.is_empty() {
+                    wrapper = wrapper.push_sql(crate::sql::TEMPLATE.r#where.left_right_space)
+                        .push_sql(&version_sql);
+                    has_where = true;
+                }
+            }
+        }
+        _ => {}
+    }
+    if !w.is_empty() {
+        if !has_where {
+            wrapper = wrapper.push_sql(crate::sql::TEMPLATE.r#where.left_right_space);
+        }
+        wrapper = wrapper.and();
+        wrapper = wrapper.push_wrapper(&w);
+    }
+    let (sql, args) = wrapper.build(driver_type);
+    let olds = match audit_plugin::<T>(executor.get_rbatis()) {
+        Some(_) => {
+            let mut olds = fetch_audit_rows::<_, T>(executor, w).await?;
+            //the row of other version will not be updated
+            if let Some(version_lock_plugin) = &executor.get_rbatis().version_lock_plugin {
+                if T::is_use_plugin(version_lock_plugin.name()) && !old_version.is_null() {
+                    olds.retain(|x| x.get(version_lock_plugin.column()) == Some(&old_version));
+                }
+            }
+            Some(olds)
+        }
+        None => None,
+    };
+    let rows_affected = executor
+        .exec_with(Some(&T::table_name()), sql.as_str(), &args)
+        .await?
+        .rows_affected;
+    if let (Some(olds), true) = (olds, rows_affected > 0) {
+        if let Some(plugin) = audit_plugin::<T>(executor.get_rbatis()) {
+            let mut records = audit::make_update_records(plugin, &table_name, olds, &set_values);
+            audit::mask_records(&mut records, &T::sensitive_columns());
+            save_audit_records(executor, records).await?;
+        }
+    }
+    if rows_affected > 0 {
+        *table = serde_json::from_value(serde_json::Value::Object(map)).into_result()?;
+        table.after_update(executor).await?;
+    }
+    return Ok(rows_affected);
+}
+
+/// fetch the stored rows of w,they are the old values of audit.
+/// the rows are locked in transaction,the lock is useless(and released at once) without transaction
+async fn fetch_audit_rows<E, T>(executor: &mut E, w: &Wrapper) -> Result<Vec<Value>>
+    where
+        E: ExecutorMut + Send + ?Sized,
+        T: CRUDTable,
+{
+    let columns = make_table_columns::<T>(w);
+    let (mut sql, args) = make_select_sql::<T>(&executor.driver_type()?, &columns, w)?;
+    if !executor.in_transaction() {
+        return executor.fetch_with(SqlOperation::Fetch, Some(&T::table_name()), &sql, &args).await;
+    }
+    match executor.driver_type()? {
+        DriverType::Mysql | DriverType::Postgres => {
+            sql.push_str(" for update");
+        }
+        DriverType::Mssql => {
+            //select * from table alias with (updlock,rowlock) join ... where ...
+            let from = format!(
+                "{} {} {} {}",
+                crate::sql::TEMPLATE.select.value,
+                columns,
+                crate::sql::TEMPLATE.from.value,
+                w.make_table_sql(&choose_dyn_table_name::<T>(w))
+            );
+            if sql.starts_with(&from) {
+                sql.insert_str(from.len(), " with (updlock,rowlock)");
+            }
+        }
+        _ => {}
+    }
+    executor.fetch_with(SqlOperation::Fetch, Some(&T::table_name()), &sql, &args).await
+}
+
+/// the change of audited table(insert records into audit_table()) must run in an transaction,
+/// so the change and the records are committed together.
+/// CRUDMut begin it on the connection of executor(ExecutorMut::begin_local()),never acquire an other connection
+fn need_audit_tx<T>(rb: &Rbatis, in_transaction: bool) -> bool
+    where
+        T: CRUDTable,
+{
+    !in_transaction && audit_plugin::<T>(rb).map(|x| x.audit_table().is_some()).unwrap_or(false)
+}
+
+/// commit the local tx if result is ok,or rollback
+async fn end_local_tx<R>(tx: LocalTxExecutor<'_>, result: Result<R>) -> Result<R> {
+    match result {
+        Ok(v) => {
+            tx.commit().await?;
+            Ok(v)
+        }
+        Err(e) => {
+            tx.rollback().await?;
+            Err(e)
+        }
+    }
+}
+
+/// commit the tx if result is ok,or rollback
+async fn end_tx<R>(mut tx: RBatisTxExecutor<'_>, result: Result<R>) -> Result<R> {
+    match result {
+        Ok(v) => {
+            tx.commit().await?;
+            Ok(v)
+        }
+        Err(e) => {
+            tx.rollback().await?;
+            Err(e)
+        }
+    }
+}
+
+/// insert the records into audit table by the executor,or send them to the sink of audit plugin
+async fn save_audit_records<E>(executor: &mut E, records: Vec<AuditRecord>) -> Result<()>
+    where
+        E: ExecutorMut + Send + ?Sized,
+{
+    if records.is_empty() {
+        return Ok(());
+    }
+    let audit_table = match &executor.get_rbatis().audit_plugin {
+        Some(plugin) => plugin.audit_table().map(|x| x.to_string()),
+        None => return Ok(()),
+    };
+    match audit_table {
+        Some(audit_table) => {
+            let (sql, args) = audit::make_audit_sql(&executor.driver_type()?, &audit_table, &records);
+            executor.exec_with(Some(&audit_table), &sql, &args).await?;
+        }
+        None => {
+            let rb = executor.get_rbatis();
+            if let Some(plugin) = &rb.audit_plugin {
+                plugin.sink(rb, records).await?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn make_select_sql<T>(driver_type: &DriverType, column: &str, w: &Wrapper) -> Result<(String, Vec<Value>)>
     where
         T: CRUDTable,
 {
-    let driver_type = rb.driver_type()?;
     let table_name = choose_dyn_table_name::<T>(w);
-    Ok(format!(
+    let (where_sql, args) = w.build(driver_type);
+    Ok((format!(
         "{} {} {} {} {}",
         crate::sql::TEMPLATE.select.value,
         column,
         crate::sql::TEMPLATE.from.value,
-        table_name,
-        driver_type.make_where(&w.sql)
-    ))
+        w.make_from_sql(&table_name),
+        driver_type.make_where(&where_sql)
+    ), args))
+}
+
+/// table columns of T,columns will be qualified with alias if the wrapper have joins
+fn make_table_columns<T>(w: &Wrapper) -> String
+    where
+        T: CRUDTable,
+{
+    let columns = T::table_columns();
+    if w.joins().is_empty() {
+        return columns;
+    }
+    let table_name = T::table_name();
+    let alias = w.get_alias(&table_name);
+    columns.split(",")
+        .map(|x| format!("{}.{}", alias, x.trim()))
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
+/// columns of all join tables: alias.column as alias__column
+fn make_join_columns<T>(w: &Wrapper) -> String
+    where
+        T: CRUDTable,
+{
+    let table_name = T::table_name();
+    let main_columns = T::table_columns();
+    let mut tables = vec![(w.get_alias(&table_name), main_columns.as_str())];
+    for x in w.joins() {
+        tables.push((&x.alias, &x.columns));
+    }
+    let mut sql = String::new();
+    for (alias, columns) in tables {
+        for column in columns.split(",") {
+            let column = column.trim();
+            sql.push_str(&format!("{}.{} {} {}{}{},",
+                                  alias,
+                                  column,
+                                  crate::sql::TEMPLATE.r#as.value,
+                                  alias,
+                                  JOIN_COLUMN_SEPARATOR,
+                                  crate::utils::string_util::un_packing_string(column)));
+        }
+    }
+    sql.pop();
+    sql
+}
+
+/// separator of the join column alias,for example: "a__id"
+pub const JOIN_COLUMN_SEPARATOR: &str = "__";
+
+/// split row {"a__id":1,"u__id":2} to JoinRow [("a",{"id":1}),("u",{"id":2})]
+fn make_join_row<T>(w: &Wrapper, mut row: Map<String, Value>) -> JoinRow
+    where
+        T: CRUDTable,
+{
+    let table_name = T::table_name();
+    let main_columns = T::table_columns();
+    let mut tables = vec![(w.get_alias(&table_name), main_columns.as_str())];
+    for x in w.joins() {
+        tables.push((&x.alias, &x.columns));
+    }
+    let mut join_row = JoinRow { tables: Vec::with_capacity(tables.len()) };
+    for (alias, columns) in tables {
+        let mut table = Map::new();
+        for column in columns.split(",") {
+            let column = crate::utils::string_util::un_packing_string(column.trim());
+            let key = format!("{}{}{}", alias, JOIN_COLUMN_SEPARATOR, column);
+            table.insert(column.to_string(), row.remove(&key).unwrap_or(Value::Null));
+        }
+        if table.values().all(|x| x.is_null()) {
+            join_row.tables.push((alias.to_string(), Value::Null));
+        } else {
+            join_row.tables.push((alias.to_string(), Value::Object(table)));
+        }
+    }
+    join_row
+}
+
+/// joined row,deserialize as map {alias:table} or seq [table]
+struct JoinRow {
+    tables: Vec<(String, Value)>,
+}
+
+impl<'de> Deserializer<'de> for JoinRow {
+    type Error = serde_json::Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> std::result::Result<V::Value, Self::Error> where V: Visitor<'de> {
+        let mut map = MapDeserializer::new(self.tables.into_iter());
+        let v = visitor.visit_map(&mut map)?;
+        map.end()?;
+        Ok(v)
+    }
+
+    fn deserialize_seq<V>(self, visitor: V) -> std::result::Result<V::Value, Self::Error> where V: Visitor<'de> {
+        let mut seq = SeqDeserializer::new(self.tables.into_iter().map(|(_, v)| v));
+        let v = visitor.visit_seq(&mut seq)?;
+        seq.end()?;
+        Ok(v)
+    }
+
+    fn deserialize_tuple<V>(self, _len: usize, visitor: V) -> std::result::Result<V::Value, Self::Error> where V: Visitor<'de> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V>(self, _name: &'static str, _len: usize, visitor: V) -> std::result::Result<V::Value, Self::Error> where V: Visitor<'de> {
+        self.deserialize_seq(visitor)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct map struct enum identifier ignored_any
+    }
+}
+
+/// acquire an executor and call the CRUDMut change of T on it,
+/// the change of audited table run in an new transaction(not a connection and an other transaction)
+macro_rules! change {
+    ($rb:expr, $t:ty, |$executor:ident| $call:expr) => {
+        if need_audit_tx::<$t>($rb, false) {
+            let mut $executor = $rb.acquire_begin().await?;
+            let result = $call.await;
+            end_tx($executor, result).await
+        } else {
+            let mut $executor = $rb.acquire().await?;
+            $call.await
+        }
+    };
 }
 
 #[async_trait]
 impl CRUD for Rbatis {
     async fn save_by_wrapper<T>(&self, table: &T, w: &Wrapper, skips: &[Skip]) -> Result<DBExecResult> where
         T: CRUDTable {
-        let mut conn = self.acquire().await?;
-        conn.save_by_wrapper(table, w, skips).await
+        change!(self, T, |conn| conn.save_by_wrapper(table, w, skips))
     }
 
     async fn save<T>(&self, table: &T, skips: &[Skip]) -> Result<DBExecResult> where
         T: CRUDTable {
-        let mut conn = self.acquire().await?;
-        conn.save(table, skips).await
+        change!(self, T, |conn| conn.save(table, skips))
     }
 
     async fn save_batch<T>(&self, tables: &[T], skips: &[Skip]) -> Result<DBExecResult> where
         T: CRUDTable {
-        let mut conn = self.acquire().await?;
-        conn.save_batch(tables, skips).await
+        change!(self, T, |conn| conn.save_batch(tables, skips))
     }
 
     async fn save_batch_slice<T>(&self, tables: &[T], slice_len: usize, skips: &[Skip]) -> Result<DBExecResult> where
         T: CRUDTable {
-        let mut conn = self.acquire().await?;
-        conn.save_batch_slice(tables, slice_len, skips).await
+        change!(self, T, |conn| conn.save_batch_slice(tables, slice_len, skips))
     }
 
     async fn remove_by_wrapper<T>(&self, w: &Wrapper) -> Result<u64> where
         T: CRUDTable {
-        let mut conn = self.acquire().await?;
-        conn.remove_by_wrapper::<T>(w).await
+        change!(self, T, |conn| conn.remove_by_wrapper::<T>(w))
     }
 
     async fn remove_by_column<T, C>(&self, column: &str, value: &C) -> Result<u64> where
         T: CRUDTable, C: Serialize + Send + Sync {
-        let mut conn = self.acquire().await?;
-        conn.remove_by_column::<T, C>(column, value).await
+        change!(self, T, |conn| conn.remove_by_column::<T, C>(column, value))
     }
 
     async fn remove_batch_by_column<T, C>(&self, column: &str, values: &[C]) -> Result<u64> where
         T: CRUDTable, C: Serialize + Send + Sync {
-        let mut conn = self.acquire().await?;
-        conn.remove_batch_by_column::<T, C>(column, values).await
+        change!(self, T, |conn| conn.remove_batch_by_column::<T, C>(column, values))
     }
 
     /// update_by_wrapper
     /// skips: use &[Skip::Value(&serde_json::Value::Null), Skip::Column("id"), Skip::Column(column)] will skip id column and null value param
     async fn update_by_wrapper<T>(&self, table: &mut T, w: &Wrapper, skips: &[Skip]) -> Result<u64> where
         T: CRUDTable {
-        let mut conn = self.acquire().await?;
-        conn.update_by_wrapper(table, w, skips).await
+        change!(self, T, |conn| conn.update_by_wrapper(table, w, skips))
     }
 
     async fn update_by_column<T>(&self, column: &str, table: &mut T) -> Result<u64> where
         T: CRUDTable {
-        let mut conn = self.acquire().await?;
-        conn.update_by_column(column, table).await
+        change!(self, T, |conn| conn.update_by_column(column, table))
     }
 
     async fn update_batch_by_column<T>(&self, column: &str, args: &mut [T]) -> Result<u64> where
         T: CRUDTable {
-        let mut conn = self.acquire().await?;
-        conn.update_batch_by_column::<T>(column, args).await
+        change!(self, T, |conn| conn.update_batch_by_column::<T>(column, args))
     }
 
     async fn fetch_by_column<T, C>(&self, column: &str, value: &C) -> Result<T> where
@@ -984,6 +1906,21 @@ impl CRUD for Rbatis {
         conn.fetch_count_by_wrapper::<T>(w).await
     }
 
+    async fn fetch_aggregate<T, R>(&self, w: &Wrapper, aggs: &[Agg<'_>]) -> Result<R> where
+        T: CRUDTable,
+        R: DeserializeOwned + Send + Sync {
+        let mut conn = self.acquire().await?;
+        conn.fetch_aggregate::<T, R>(w, aggs).await
+    }
+
+    async fn fetch_group_aggregate<T, K, V>(&self, w: &Wrapper, aggs: &[Agg<'_>]) -> Result<Vec<(K, V)>> where
+        T: CRUDTable,
+        K: DeserializeOwned + Send + Sync,
+        V: DeserializeOwned + Send + Sync {
+        let mut conn = self.acquire().await?;
+        conn.fetch_group_aggregate::<T, K, V>(w, aggs).await
+    }
+
     async fn fetch_page_by_wrapper<T>(&self, w: &Wrapper, page: &dyn IPageRequest) -> Result<Page<T>> where
         T: CRUDTable {
         let mut conn = self.acquire().await?;
@@ -1011,6 +1948,34 @@ impl CRUD for Rbatis {
         conn.fetch_list_by_wrapper(w).await
     }
 
+    fn fetch_stream_by_wrapper<'s, T>(&'s self, w: &Wrapper, chunk_size: u64) -> BoxStream<'s, Result<T>> where
+        T: CRUDTable + 's {
+        let w = w.clone();
+        futures::stream::once(self.acquire())
+            .map(move |conn| -> BoxStream<'s, Result<T>> {
+                match conn {
+                    Ok(conn) => make_chunk_stream(Box::new(conn), &w, chunk_size),
+                    Err(e) => futures::stream::once(futures::future::ready(Err(e))).boxed(),
+                }
+            })
+            .flatten()
+            .boxed()
+    }
+
+    async fn fetch_list_by_join<T, R>(&self, w: &Wrapper) -> Result<Vec<R>> where
+        T: CRUDTable,
+        R: DeserializeOwned + Send + Sync {
+        let mut conn = self.acquire().await?;
+        conn.fetch_list_by_join::<T, R>(w).await
+    }
+
+    async fn fetch_page_by_join<T, R>(&self, w: &Wrapper, page: &dyn IPageRequest) -> Result<Page<R>> where
+        T: CRUDTable,
+        R: DeserializeOwned + Send + Sync {
+        let mut conn = self.acquire().await?;
+        conn.fetch_page_by_join::<T, R>(w, page).await
+    }
+
     /// fetch page result(prepare sql)
     async fn fetch_page<T>(
         &self,
@@ -1024,8 +1989,156 @@ impl CRUD for Rbatis {
         let mut conn = self.acquire().await?;
         conn.fetch_page(sql, args, page_request).await
     }
+
+    async fn for_each_chunk<T, F, Fut>(&self, w: &Wrapper, chunk_size: u64, f: F) -> Result<ChunkProgress>
+        where
+            T: CRUDTable,
+            F: FnMut(Vec<T>) -> Fut + Send,
+            Fut: Future<Output=Result<()>> + Send {
+        let mut progress = ChunkProgress::default();
+        self.for_each_chunk_by(w, "id", chunk_size, &mut progress, f).await?;
+        Ok(progress)
+    }
+
+    async fn for_each_chunk_by<T, F, Fut>(&self, w: &Wrapper, key_column: &str, chunk_size: u64, progress: &mut ChunkProgress, mut f: F) -> Result<()>
+        where
+            T: CRUDTable,
+            F: FnMut(Vec<T>) -> Fut + Send,
+            Fut: Future<Output=Result<()>> + Send {
+        loop {
+            let mut conn = self.acquire().await?;
+            let chunk = fetch_chunk::<_, T>(&mut conn, w, key_column, chunk_size, progress).await?;
+            drop(conn);
+            match chunk {
+                None => {
+                    return Ok(());
+                }
+                Some((batch, last_key)) => {
+                    let len = batch.len() as u64;
+                    f(batch).await?;
+                    progress.done_chunk(last_key, len);
+                    if len < chunk_size {
+                        return Ok(());
+                    }
+                }
+            }
+        }
+    }
+
+    async fn for_each_chunk_tx<'s, T, F>(&'s self, w: &Wrapper, key_column: &str, chunk_size: u64, progress: &mut ChunkProgress, mut f: F) -> Result<()>
+        where
+            T: CRUDTable,
+            F: for<'t> FnMut(Vec<T>, &'t mut RBatisTxExecutor<'s>) -> BoxFuture<'t, Result<()>> + Send + 's {
+        loop {
+            let mut tx = self.acquire_begin().await?;
+            let chunk = fetch_chunk::<_, T>(&mut tx, w, key_column, chunk_size, progress).await;
+            let chunk = match chunk {
+                Ok(v) => v,
+                Err(e) => {
+                    tx.rollback().await?;
+                    return Err(e);
+                }
+            };
+            match chunk {
+                None => {
+                    tx.commit().await?;
+                    return Ok(());
+                }
+                Some((batch, last_key)) => {
+                    let len = batch.len() as u64;
+                    match f(batch, &mut tx).await {
+                        Ok(_) => {
+                            tx.commit().await?;
+                        }
+                        Err(e) => {
+                            tx.rollback().await?;
+                            return Err(e);
+                        }
+                    }
+                    progress.done_chunk(last_key, len);
+                    if len < chunk_size {
+                        return Ok(());
+                    }
+                }
+            }
+        }
+    }
 }
 
+/// the progress of CRUD::for_each_chunk(),can be saved and used to resume
+#[derive(Clone, Debug, Default, Serialize, Deserialize, Eq, PartialEq)]
+pub struct ChunkProgress {
+    /// the key of the last row which is processed,the next chunk start after it
+    pub last_key: Option<Value>,
+    /// processed chunks
+    pub chunks: u64,
+    /// processed rows
+    pub rows: u64,
+}
+
+impl ChunkProgress {
+    /// resume from last_key
+    pub fn resume<K>(last_key: K) -> Self where K: Serialize {
+        Self {
+            last_key: Some(json!(last_key)),
+            chunks: 0,
+            rows: 0,
+        }
+    }
+
+    fn done_chunk(&mut self, last_key: Value, rows: u64) {
+        self.last_key = Some(last_key);
+        self.chunks += 1;
+        self.rows += rows;
+    }
+}
+
+/// aggregate function of CRUD::fetch_aggregate()
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Agg<'a> {
+    /// count(column),column can be "*"
+    Count(&'a str),
+    /// count(distinct column)
+    CountDistinct(&'a str),
+    Sum(&'a str),
+    Avg(&'a str),
+    Max(&'a str),
+    Min(&'a str),
+}
+
+impl<'a> Agg<'a> {
+    fn func_column(&self) -> (&'static str, &'a str) {
+        match self {
+            Agg::Count(c) => ("count", c),
+            Agg::CountDistinct(c) => ("count_distinct", c),
+            Agg::Sum(c) => ("sum", c),
+            Agg::Avg(c) => ("avg", c),
+            Agg::Max(c) => ("max", c),
+            Agg::Min(c) => ("min", c),
+        }
+    }
+
+    /// column alias of the aggregate value,for example: Agg::Sum("amount") => "sum_amount",Agg::Count("*") => "count"
+    pub fn alias(&self) -> String {
+        let (func, column) = self.func_column();
+        let column = column.rsplit('.').next().unwrap_or(column).trim();
+        let column = crate::utils::string_util::un_packing_string(column);
+        if column == "*" {
+            return func.to_string();
+        }
+        format!("{}_{}", func, column)
+    }
+
+    /// sql of the aggregate,for example: Agg::Sum("amount") => "sum(amount) as sum_amount"
+    pub fn to_sql(&self) -> String {
+        let (func, column) = self.func_column();
+        let value = match self {
+            Agg::CountDistinct(_) => format!("count(distinct {})", column),
+            _ => format!("{}({})", func, column),
+        };
+        format!("{} {} {}", value, crate::sql::TEMPLATE.r#as.value, self.alias())
+    }
+}
 
 /// skip column or param value
 pub enum Skip<'a> {
@@ -1086,6 +2199,7 @@ impl<T, P> DerefMut for DynTableColumn<T, P> where T: CRUDTable, P: TableColumnP
     }
 }
 
+#[async_trait]
 impl<T, P> CRUDTable for DynTableColumn<T, P> where T: CRUDTable, P: TableColumnProvider {
     /// is enable use plugin
     fn is_use_plugin(plugin_name: &str) -> bool { T::is_use_plugin(plugin_name) }
@@ -1098,6 +2212,10 @@ impl<T, P> CRUDTable for DynTableColumn<T, P> where T: CRUDTable, P: TableColumn
         P::table_columns()
     }
 
+    fn sensitive_columns() -> Vec<String> {
+        T::sensitive_columns()
+    }
+
     ///format column
     fn do_format_column(driver_type: &DriverType, column: &str, data: &mut String) {
         T::do_format_column(driver_type, column, data)
@@ -1128,4 +2246,46 @@ impl<T, P> CRUDTable for DynTableColumn<T, P> where T: CRUDTable, P: TableColumn
     fn get(&self, column: &str) -> serde_json::Value {
         T::get(self, column)
     }
+
+    async fn before_insert<E>(&self, executor: &mut E) -> Result<Option<Self>>
+        where
+            E: ExecutorMut + Send + ?Sized,
+    {
+        Ok(self.inner.before_insert(executor).await?.map(|inner| DynTableColumn { inner, p: PhantomData }))
+    }
+
+    async fn after_insert<E>(&self, executor: &mut E) -> Result<()>
+        where
+            E: ExecutorMut + Send + ?Sized,
+    {
+        self.inner.after_insert(executor).await
+    }
+
+    async fn before_update<E>(&mut self, executor: &mut E) -> Result<()>
+        where
+            E: ExecutorMut + Send + ?Sized,
+    {
+        self.inner.before_update(executor).await
+    }
+
+    async fn after_update<E>(&mut self, executor: &mut E) -> Result<()>
+        where
+            E: ExecutorMut + Send + ?Sized,
+    {
+        self.inner.after_update(executor).await
+    }
+
+    async fn before_delete<E>(executor: &mut E, w: &Wrapper) -> Result<()>
+        where
+            E: ExecutorMut + Send + ?Sized,
+    {
+        T::before_delete(executor, w).await
+    }
+
+    async fn after_fetch<E>(&mut self, executor: &mut E) -> Result<()>
+        where
+            E: ExecutorMut + Send + ?Sized,
+    {
+        self.inner.after_fetch(executor).await
+    }
 }
\ No newline at end of file
@@ -1,3 +1,4 @@
+use std::borrow::Cow;
 use std::ops::{Deref, DerefMut};
 
 use async_trait::async_trait;
@@ -7,14 +8,22 @@ use serde::de::DeserializeOwned;
 use serde::Serialize;
 use serde_json::Value;
 
+use crate::core::convert::ResultCodec;
 use crate::core::db::{DBPool, DBPoolConn, DBQuery, DBTx};
 use crate::core::Error;
 use crate::crud::{CRUD, CRUDMut};
 use crate::DriverType;
+use crate::plugin::intercept::{Intercept, InterceptContext, InterceptResult, SqlOperation, SqlOutput};
+use crate::plugin::log::{SqlLogEvent, SqlLogPhase};
 use crate::plugin::page::{IPageRequest, Page};
+use crate::plugin::slow_query::{make_explain_sql, SlowQuery};
+#[cfg(feature = "tracing")]
+use crate::plugin::trace;
 use crate::rbatis::Rbatis;
-use crate::utils::string_util;
+use crate::wrapper::check_params;
 use futures::executor::block_on;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Instant;
 
 
 #[async_trait]
@@ -32,6 +41,7 @@ pub trait RbatisRef {
         sql: &'arg str,
         arg: &Vec<serde_json::Value>,
     ) -> Result<DBQuery<'arg>, Error> {
+        check_params(driver_type, arg)?;
         let mut q: DBQuery = DBPool::make_db_query(driver_type, sql)?;
         for x in arg {
             (self.get_rbatis().encoder)(&mut q, x)?;
@@ -46,8 +56,40 @@ impl RbatisRef for Rbatis {
     }
 }
 
+/// the executor of sql.
+/// exec_with()/fetch_with() have default impls,a custom executor only need impl exec()/fetch()
 #[async_trait]
 pub trait ExecutorMut: RbatisRef {
+    /// exec sql,table is the table name if known,it will be passed to the Intercept chain.
+    /// the default impl call exec() and drop the table
+    async fn exec_with(&mut self, _table: Option<&str>, sql: &str, args: &Vec<serde_json::Value>) -> Result<DBExecResult, Error> {
+        self.exec(sql, args).await
+    }
+
+    /// fetch sql,operation and table(if known) will be passed to the Intercept chain.
+    /// the default impl call fetch() and drop the operation and table
+    async fn fetch_with<T>(&mut self, _operation: SqlOperation, _table: Option<&str>, sql: &str, args: &Vec<serde_json::Value>) -> Result<T, Error> where T: DeserializeOwned {
+        self.fetch(sql, args).await
+    }
+
+    /// the executor is an transaction,CRUDMut run multi sql of one change in an new transaction if it is false
+    fn in_transaction(&self) -> bool {
+        false
+    }
+
+    /// begin an transaction on the connection of self(never acquire an new connection of pool),
+    /// CRUDMut use it to run multi sql of one change in an transaction if in_transaction() is false.
+    /// the default impl not support it
+    async fn begin_local<'e>(&'e mut self) -> Result<LocalTxExecutor<'e>, Error> {
+        Err(Error::from("[rbatis] the executor not support local transaction,please use an tx executor(rb.acquire_begin())!"))
+    }
+
+    /// the parent span of sql spans,it is the tx span of RBatisTxExecutor
+    #[cfg(feature = "tracing")]
+    fn trace_parent(&self) -> Option<&tracing::Span> {
+        None
+    }
+
     async fn exec(&mut self, sql: &str, args: &Vec<serde_json::Value>) -> Result<DBExecResult, Error>;
     async fn fetch<T>(&mut self, sql: &str, args: &Vec<serde_json::Value>) -> Result<T, Error> where T: DeserializeOwned;
 }
@@ -64,102 +106,269 @@ impl<'a> RBatisConnExecutor<'a> {
     }
 }
 
+/// the Intercept chain of rb,sql_intercepts first
+fn intercept_chain(rb: &Rbatis) -> impl Iterator<Item=&dyn Intercept> {
+    rb.sql_intercepts.iter()
+        .map(|x| x as &dyn Intercept)
+        .chain(rb.intercepts.iter().map(|x| x.as_ref()))
+}
+
+/// run before() of the Intercept chain,stop at the first error
+async fn run_before(rb: &Rbatis, context: &InterceptContext, sql: &mut String, args: &mut Vec<serde_json::Value>) -> Result<(), Error> {
+    for item in intercept_chain(rb) {
+        item.before(rb, context, sql, args).await?;
+    }
+    Ok(())
+}
+
+/// record the sql to slow_query_plugin if it is slow
+fn record_slow_query(rb: &Rbatis, context: &InterceptContext, sql: &str, args: &Vec<serde_json::Value>, elapsed: std::time::Duration, explain: Option<serde_json::Value>) {
+    if let Some(plugin) = &rb.slow_query_plugin {
+        plugin.record(rb, SlowQuery {
+            sql: sql.to_string(),
+            args: args.clone(),
+            elapsed,
+            operation: context.operation,
+            table: context.table.clone(),
+            in_transaction: context.in_transaction,
+            explain,
+        });
+    }
+}
+
+/// the args for log,redacted by the redaction policy of log plugin
+fn redact_log_args<'a>(rb: &Rbatis, driver_type: &DriverType, sql: &str, args: &'a Vec<serde_json::Value>) -> Cow<'a, [serde_json::Value]> {
+    if !rb.log_plugin.is_enable() {
+        return Cow::Borrowed(args);
+    }
+    match rb.log_plugin.redact_policy() {
+        Some(policy) => Cow::Owned(policy.redact_args(driver_type, sql, args)),
+        None => Cow::Borrowed(args),
+    }
+}
+
+/// is the sql use time over threshold of slow_query_plugin
+fn is_slow_query(rb: &Rbatis, elapsed: std::time::Duration) -> bool {
+    match &rb.slow_query_plugin {
+        Some(plugin) => elapsed >= plugin.threshold(),
+        None => false,
+    }
+}
+
+/// await the future in the span(only feature 'tracing'),the span is not evaluated without it
+#[cfg(feature = "tracing")]
+macro_rules! traced {
+    ($span:expr, $future:expr) => {{
+        let span = $span;
+        tracing::Instrument::instrument($future, span).await
+    }};
+}
+
+#[cfg(not(feature = "tracing"))]
+macro_rules! traced {
+    ($span:expr, $future:expr) => {
+        $future.await
+    };
+}
+
 macro_rules! impl_executor {
-    ($t:ty) => {
+    ($t:ty, $in_transaction:expr) => {
 #[async_trait]
 impl<'a> ExecutorMut for $t {
-    async fn exec(&mut self, sql: &str, args: &Vec<serde_json::Value>) -> Result<DBExecResult, Error> {
-        let mut sql = sql.to_string();
-        let mut args = args.clone();
-        let is_prepared = args.len() > 0;
-        for item in &self.get_rbatis().sql_intercepts {
-            item.do_intercept(self.get_rbatis(), &mut sql, &mut args, is_prepared)?;
-        }
-        if self.get_rbatis().log_plugin.is_enable() {
-            self.get_rbatis().log_plugin.info(
-                &format!(
-                    "Exec   ==> {}\n{}[rbatis] [{}] Args   ==> {}",
-                    &sql,
-                    string_util::LOG_SPACE,
-                    "",
-                    serde_json::Value::Array(args.clone()).to_string()
-                ),
-            );
-        }
-        let result;
-        if is_prepared {
-            let q: DBQuery = self.bind_arg(&self.conn.driver_type, &sql, &args)?;
-            result = self.conn.exec_prepare(q).await;
-        } else {
-            result = self.conn.exec(&sql).await;
-        }
-        if self.get_rbatis().log_plugin.is_enable() {
-            match &result {
-                Ok(result) => {
-                    self.get_rbatis().log_plugin.info(
-                        &format!("RowsAffected <== {}", result.rows_affected),
-                    );
-                }
-                Err(e) => {
-                    self.get_rbatis().log_plugin
-                        .error(&format!("ReturnErr  <== {}", e));
-                }
-            }
-        }
-        return result;
-    }
-
-    async fn fetch<T>(&mut self, sql: &str, args: &Vec<serde_json::Value>) -> Result<T, Error> where T: DeserializeOwned {
-        let mut sql = sql.to_string();
-        let mut args = args.clone();
-        let is_prepared = args.len() > 0;
-        for item in &self.get_rbatis().sql_intercepts {
-            item.do_intercept(self.get_rbatis(), &mut sql, &mut args, is_prepared)?;
-        }
-        if self.get_rbatis().log_plugin.is_enable() {
-            self.get_rbatis().log_plugin.info(
-                &format!(
-                    "Fetch  ==> {}\n{}[rbatis] [{}] Args   ==> {}",
-                    &sql,
-                    string_util::LOG_SPACE,
-                    "",
-                    serde_json::Value::Array(args.clone()).to_string()
-                ),
-            );
-        }
-        if is_prepared {
-            let q: DBQuery = self.bind_arg(&self.conn.driver_type, &sql, &args)?;
-            let result = self.conn.fetch_parperd(q).await;
+    async fn exec_with(&mut self, table: Option<&str>, sql: &str, args: &Vec<serde_json::Value>) -> Result<DBExecResult, Error> {
+        let context = InterceptContext {
+            operation: SqlOperation::Exec,
+            table: table.map(|x| x.to_string()),
+            in_transaction: $in_transaction,
+            is_prepared: args.len() > 0,
+        };
+        traced!(trace::make_sql_span(self.trace_parent(), &self.conn.driver_type, &context), async {
+            let mut sql = sql.to_string();
+            let mut args = args.clone();
+            //the error of before() or bind is the result,after() and log still see it
+            let before_result = run_before(self.get_rbatis(), &context, &mut sql, &mut args).await;
+            #[cfg(feature = "tracing")]
+            trace::record_statement(&tracing::Span::current(), &context, &sql);
+            let log_args = redact_log_args(self.get_rbatis(), &self.conn.driver_type, &sql, &args);
             if self.get_rbatis().log_plugin.is_enable() {
-                match &result {
-                    Ok(result) => {
-                        self.get_rbatis().log_plugin
-                            .info(&format!("ReturnRows <== {}", result.1));
-                    }
-                    Err(e) => {
-                        self.get_rbatis().log_plugin
-                            .error(&format!("ReturnErr  <== {}", e));
+                self.get_rbatis().log_plugin.log_sql(&SqlLogEvent {
+                    phase: SqlLogPhase::Request,
+                    operation: context.operation,
+                    sql: &sql,
+                    args: &log_args,
+                    rows_affected: None,
+                    elapsed: None,
+                    error: None,
+                    tx_id: self.tx_id(),
+                });
+            }
+            let start = Instant::now();
+            let result = match before_result {
+                Err(e) => Err(e),
+                Ok(_) if context.is_prepared => {
+                    match self.bind_arg(&self.conn.driver_type, &sql, &args) {
+                        Ok(q) => self.conn.exec_prepare(q).await,
+                        Err(e) => Err(e),
                     }
                 }
+                Ok(_) => self.conn.exec(&sql).await,
+            };
+            let elapsed = start.elapsed();
+            #[cfg(feature = "tracing")]
+            trace::record_result(&tracing::Span::current(), &result, |x| x.rows_affected);
+            if self.get_rbatis().log_plugin.is_enable() {
+                self.get_rbatis().log_plugin.log_sql(&SqlLogEvent {
+                    phase: SqlLogPhase::Response,
+                    operation: context.operation,
+                    sql: &sql,
+                    args: &log_args,
+                    rows_affected: result.as_ref().ok().map(|x| x.rows_affected),
+                    elapsed: Some(elapsed),
+                    error: result.as_ref().err(),
+                    tx_id: self.tx_id(),
+                });
             }
-            return Ok(result?.0);
-        } else {
-            let result = self.conn.fetch(&sql.to_owned()).await;
+            if let Some(plugin) = &self.get_rbatis().metrics_plugin {
+                let rows = result.as_ref().map(|x| x.rows_affected).unwrap_or(0);
+                plugin.record_sql(&context, &sql, elapsed, rows, result.is_err());
+            }
+            if is_slow_query(self.get_rbatis(), elapsed) {
+                record_slow_query(self.get_rbatis(), &context, &sql, &args, elapsed, None);
+            }
+            let intercept_result = InterceptResult {
+                output: result.as_ref().map(SqlOutput::Exec),
+                elapsed,
+            };
+            for item in intercept_chain(self.get_rbatis()) {
+                item.after(self.get_rbatis(), &context, &sql, &args, &intercept_result).await?;
+            }
+            return result;
+        })
+    }
+
+    async fn fetch_with<T>(&mut self, operation: SqlOperation, table: Option<&str>, sql: &str, args: &Vec<serde_json::Value>) -> Result<T, Error> where T: DeserializeOwned {
+        let context = InterceptContext {
+            operation,
+            table: table.map(|x| x.to_string()),
+            in_transaction: $in_transaction,
+            is_prepared: args.len() > 0,
+        };
+        traced!(trace::make_sql_span(self.trace_parent(), &self.conn.driver_type, &context), async {
+            let mut sql = sql.to_string();
+            let mut args = args.clone();
+            //the error of before() or bind is the result,after() and log still see it
+            let before_result = run_before(self.get_rbatis(), &context, &mut sql, &mut args).await;
+            #[cfg(feature = "tracing")]
+            trace::record_statement(&tracing::Span::current(), &context, &sql);
+            let log_args = redact_log_args(self.get_rbatis(), &self.conn.driver_type, &sql, &args);
             if self.get_rbatis().log_plugin.is_enable() {
-                match &result {
-                    Ok(result) => {
-                        self.get_rbatis().log_plugin
-                            .info(&format!("ReturnRows <== {}", result.1));
+                self.get_rbatis().log_plugin.log_sql(&SqlLogEvent {
+                    phase: SqlLogPhase::Request,
+                    operation: context.operation,
+                    sql: &sql,
+                    args: &log_args,
+                    rows_affected: None,
+                    elapsed: None,
+                    error: None,
+                    tx_id: self.tx_id(),
+                });
+            }
+            let start = Instant::now();
+            //fetch as json,T need not be Send when after() is awaited
+            let result: Result<(serde_json::Value, usize), Error> = match before_result {
+                Err(e) => Err(e),
+                Ok(_) if context.is_prepared => {
+                    match self.bind_arg(&self.conn.driver_type, &sql, &args) {
+                        Ok(q) => self.conn.fetch_parperd(q).await,
+                        Err(e) => Err(e),
                     }
-                    Err(e) => {
-                        self.get_rbatis().log_plugin
-                            .error(&format!("ReturnErr  <== {}", e));
+                }
+                Ok(_) => self.conn.fetch(&sql.to_owned()).await,
+            };
+            let elapsed = start.elapsed();
+            #[cfg(feature = "tracing")]
+            trace::record_result(&tracing::Span::current(), &result, |x| x.1 as u64);
+            if self.get_rbatis().log_plugin.is_enable() {
+                self.get_rbatis().log_plugin.log_sql(&SqlLogEvent {
+                    phase: SqlLogPhase::Response,
+                    operation: context.operation,
+                    sql: &sql,
+                    args: &log_args,
+                    rows_affected: result.as_ref().ok().map(|x| x.1 as u64),
+                    elapsed: Some(elapsed),
+                    error: result.as_ref().err(),
+                    tx_id: self.tx_id(),
+                });
+            }
+            if let Some(plugin) = &self.get_rbatis().metrics_plugin {
+                let rows = result.as_ref().map(|x| x.1 as u64).unwrap_or(0);
+                plugin.record_sql(&context, &sql, elapsed, rows, result.is_err());
+            }
+            if is_slow_query(self.get_rbatis(), elapsed) {
+                let mut explain = None;
+                //not explain in transaction,it hold the locks of tx longer
+                if result.is_ok() && !context.in_transaction && self.get_rbatis().slow_query_plugin.as_ref().map(|x| x.is_explain()).unwrap_or(false) {
+                    if let Some(explain_sql) = make_explain_sql(&self.conn.driver_type, &sql) {
+                        let explain_result: Result<(Vec<serde_json::Value>, usize), Error>;
+                        if context.is_prepared {
+                            match self.bind_arg(&self.conn.driver_type, &explain_sql, &args) {
+                                Ok(q) => {
+                                    explain_result = self.conn.fetch_parperd(q).await;
+                                }
+                                Err(e) => {
+                                    explain_result = Err(e);
+                                }
+                            }
+                        } else {
+                            explain_result = self.conn.fetch(&explain_sql).await;
+                        }
+                        match explain_result {
+                            Ok((rows, _)) => {
+                                explain = Some(serde_json::Value::Array(rows));
+                            }
+                            Err(e) => {
+                                self.get_rbatis().log_plugin.warn(&format!("ExplainErr <== {}", e));
+                            }
+                        }
                     }
                 }
+                record_slow_query(self.get_rbatis(), &context, &sql, &args, elapsed, explain);
             }
-            return Ok(result?.0);
+            let intercept_result = InterceptResult {
+                output: result.as_ref().map(|x| SqlOutput::Rows(x.1)),
+                elapsed,
+            };
+            for item in intercept_chain(self.get_rbatis()) {
+                item.after(self.get_rbatis(), &context, &sql, &args, &intercept_result).await?;
+            }
+            return serde_json::from_value(result?.0).into_result();
+        })
+    }
+
+    fn in_transaction(&self) -> bool {
+        $in_transaction
+    }
+
+    async fn begin_local<'e>(&'e mut self) -> Result<LocalTxExecutor<'e>, Error> {
+        let rb = self.rb;
+        match self.local_conn() {
+            Some(conn) => LocalTxExecutor::begin(rb, conn).await,
+            None => Err(Error::from("[rbatis] the executor is in transaction already!")),
         }
     }
+
+    #[cfg(feature = "tracing")]
+    fn trace_parent(&self) -> Option<&tracing::Span> {
+        self.trace_span()
+    }
+
+    async fn exec(&mut self, sql: &str, args: &Vec<serde_json::Value>) -> Result<DBExecResult, Error> {
+        self.exec_with(None, sql, args).await
+    }
+
+    async fn fetch<T>(&mut self, sql: &str, args: &Vec<serde_json::Value>) -> Result<T, Error> where T: DeserializeOwned {
+        self.fetch_with(SqlOperation::Fetch, None, sql, args).await
+    }
 }
 
 impl RbatisRef for $t {
@@ -171,41 +380,172 @@ impl RbatisRef for $t {
 };
 }
 
-impl_executor!(RBatisConnExecutor<'_>);
+impl_executor!(RBatisConnExecutor<'_>, false);
 
 impl<'a> RBatisConnExecutor<'a> {
     pub async fn begin(self) -> crate::Result<RBatisTxExecutor<'a>> {
         let tx = self.conn.begin().await?;
         return Ok(RBatisTxExecutor {
+            #[cfg(feature = "tracing")]
+            span: trace::make_tx_span(&tx.driver_type),
+            tx_id: new_tx_id(),
             conn: tx,
             rb: self.rb,
         });
     }
+
+    /// always None,see RBatisTxExecutor::tx_id()
+    pub fn tx_id(&self) -> Option<u64> {
+        None
+    }
+
+    /// the connection for begin_local()
+    fn local_conn(&mut self) -> Option<&mut DBPoolConn> {
+        Some(&mut self.conn)
+    }
+
+    #[cfg(feature = "tracing")]
+    fn trace_span(&self) -> Option<&tracing::Span> {
+        None
+    }
 }
 
 #[derive(Debug)]
 pub struct RBatisTxExecutor<'a> {
     pub conn: DBTx,
     pub rb: &'a Rbatis,
+    /// the id of tx,unique in process,see SqlLogEvent
+    pub(crate) tx_id: u64,
+    /// the tx span,from begin to commit/rollback
+    #[cfg(feature = "tracing")]
+    pub(crate) span: tracing::Span,
 }
 
 impl<'a> RBatisTxExecutor<'a> {
     pub fn as_executor(&'a mut self) -> RbatisExecutor<'a> {
         self.into()
     }
+
+    pub fn tx_id(&self) -> Option<u64> {
+        Some(self.tx_id)
+    }
+
+    /// already in transaction
+    fn local_conn(&mut self) -> Option<&mut DBPoolConn> {
+        None
+    }
 }
 
-impl_executor!(RBatisTxExecutor<'_>);
+static TX_ID: AtomicU64 = AtomicU64::new(1);
+
+/// make an new tx id
+pub(crate) fn new_tx_id() -> u64 {
+    TX_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+impl_executor!(RBatisTxExecutor<'_>, true);
+
+/// the transaction on the connection of RBatisConnExecutor(begin/commit/rollback sql),see ExecutorMut::begin_local().
+/// the transaction must be end by commit() or rollback()
+#[derive(Debug)]
+pub struct LocalTxExecutor<'a> {
+    conn: &'a mut DBPoolConn,
+    rb: &'a Rbatis,
+    tx_id: u64,
+    #[cfg(feature = "tracing")]
+    span: tracing::Span,
+}
+
+impl_executor!(LocalTxExecutor<'_>, true);
+
+impl<'a> LocalTxExecutor<'a> {
+    async fn begin(rb: &'a Rbatis, conn: &'a mut DBPoolConn) -> crate::Result<LocalTxExecutor<'a>> {
+        conn.exec(local_tx_sql("begin", &conn.driver_type).as_str()).await?;
+        return Ok(Self {
+            #[cfg(feature = "tracing")]
+            span: trace::make_tx_span(&conn.driver_type),
+            tx_id: new_tx_id(),
+            conn,
+            rb,
+        });
+    }
+
+    pub async fn commit(self) -> crate::Result<()> {
+        self.end("commit").await
+    }
+
+    pub async fn rollback(self) -> crate::Result<()> {
+        self.end("rollback").await
+    }
+
+    async fn end(self, end: &str) -> crate::Result<()> {
+        let result = self.conn.exec(local_tx_sql(end, &self.conn.driver_type).as_str()).await.map(|_| ());
+        #[cfg(feature = "tracing")]
+        trace::record_tx_end(&self.span, end, &result);
+        return Ok(result?);
+    }
+
+    pub fn tx_id(&self) -> Option<u64> {
+        Some(self.tx_id)
+    }
+
+    /// already in transaction
+    fn local_conn(&mut self) -> Option<&mut DBPoolConn> {
+        None
+    }
+
+    #[cfg(feature = "tracing")]
+    fn trace_span(&self) -> Option<&tracing::Span> {
+        Some(&self.span)
+    }
+}
+
+/// begin/commit/rollback sql,sqlserver need 'transaction'
+fn local_tx_sql(action: &str, driver_type: &DriverType) -> String {
+    match driver_type {
+        DriverType::Mssql => format!("{} transaction", action),
+        _ => action.to_string(),
+    }
+}
 
 impl<'a> RBatisTxExecutor<'a> {
     pub async fn begin(&mut self) -> crate::Result<()> {
-        return Ok(self.conn.begin().await?);
+        self.conn.begin().await?;
+        #[cfg(feature = "tracing")]
+        {
+            self.span = trace::make_tx_span(&self.conn.driver_type);
+        }
+        return Ok(());
     }
     pub async fn commit(&mut self) -> crate::Result<()> {
-        return Ok(self.conn.commit().await?);
+        let result = self.conn.commit().await;
+        #[cfg(feature = "tracing")]
+        self.end_span("commit", &result);
+        return Ok(result?);
     }
     pub async fn rollback(&mut self) -> crate::Result<()> {
-        return Ok(self.conn.rollback().await?);
+        let result = self.conn.rollback().await;
+        #[cfg(feature = "tracing")]
+        self.end_span("rollback", &result);
+        return Ok(result?);
+    }
+
+    /// record the end and close the tx span
+    #[cfg(feature = "tracing")]
+    fn end_span(&mut self, end: &str, result: &crate::Result<()>) {
+        trace::record_tx_end(&self.span, end, result);
+        self.span = tracing::Span::none();
+    }
+
+    #[cfg(feature = "tracing")]
+    fn trace_span(&self) -> Option<&tracing::Span> {
+        Some(&self.span)
+    }
+
+    /// the tx span,from begin to commit/rollback
+    #[cfg(feature = "tracing")]
+    pub fn span(&self) -> &tracing::Span {
+        &self.span
     }
 
     pub fn take_conn(self) -> Option<DBPoolConn> {
@@ -19,11 +19,13 @@ pub use rbatis_core as core;
 pub use rbatis_sql::{expr, push_index, rb_html, rb_py, sql_index};
 pub use rbatis_sql::ops::*;
 
-pub use rbatis_macro_driver::{crud_table, CRUDTable, html_sql, py_sql, sql};
+pub use rbatis_macro_driver::{crud_table, CRUDTable, WrapperFilter, html_sql, py_sql, sql};
 
 pub use crate::core::{convert::StmtConvert, db::DriverType, error::Error, error::Result};
+pub use async_trait::async_trait;
 
 
+pub mod column;
 pub mod crud;
 pub mod plugin;
 pub mod rbatis;
@@ -0,0 +1,308 @@
+use std::fmt::{Debug, Formatter};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::core::convert::StmtConvert;
+use crate::core::db::DriverType;
+use crate::core::Error;
+use crate::rbatis::Rbatis;
+
+/// the kind of row change
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum AuditOperation {
+    Insert,
+    Update,
+    Delete,
+}
+
+impl AuditOperation {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditOperation::Insert => "insert",
+            AuditOperation::Update => "update",
+            AuditOperation::Delete => "delete",
+        }
+    }
+}
+
+/// an row change record
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AuditRecord {
+    pub table: String,
+    /// value of the primary key,null if unknown(for example the auto increment id of batch insert)
+    pub primary_key: Value,
+    pub operation: AuditOperation,
+    /// Insert: None,Update: the changed columns before,Delete: the stored row
+    pub old: Option<Value>,
+    /// Insert: the row,Update: the changed columns after,Delete: None
+    pub new: Option<Value>,
+    pub actor: Option<String>,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// data change audit plugin,CRUDMut save*/update_by_wrapper/remove_by_wrapper record every changed row.
+/// the records are insert into audit_table() in the same transaction of the change,
+/// if the executor is not RBatisTxExecutor,the change and records run in an new transaction(of an new connection).
+/// the old rows are read with 'for update'(mssql: 'with (updlock,rowlock)'),the sensitive columns are masked.
+/// records are send to sink() after the change if audit_table() is None.
+/// table can disable it by CRUDTable::is_use_plugin()
+#[async_trait]
+pub trait AuditPlugin: Send + Sync + Debug {
+    ///the name
+    fn name(&self) -> &str {
+        std::any::type_name::<Self>()
+    }
+
+    /// the primary key column of table,default "id"
+    fn primary_key(&self, _table: &str) -> String {
+        "id".to_string()
+    }
+
+    /// who made the change,for example the login user of current task
+    fn actor(&self) -> Option<String> {
+        None
+    }
+
+    /// the table save records,None will send records to sink()
+    fn audit_table(&self) -> Option<&str>;
+
+    /// receive the records if audit_table() is None
+    async fn sink(&self, _rb: &Rbatis, _records: Vec<AuditRecord>) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+/// the user sink of audit records,for example write to file or message queue
+#[async_trait]
+pub trait AuditSink: Send + Sync + Debug {
+    async fn write(&self, rb: &Rbatis, records: Vec<AuditRecord>) -> Result<(), Error>;
+}
+
+/// save records into an audit table,or send to an AuditSink.
+/// the audit table columns are: table_name,primary_key,operation,old_value,new_value,actor,create_time(all of them are string),
+/// for example:
+///     create table sys_audit (id bigint auto_increment primary key,table_name varchar(64),primary_key varchar(64),
+///         operation varchar(16),old_value text,new_value text,actor varchar(64),create_time varchar(32));
+///     rb.set_audit_plugin(Some(RbatisAuditPlugin::table("sys_audit").actor(|| Some(current_user()))));
+///     //or
+///     rb.set_audit_plugin(Some(RbatisAuditPlugin::sink(MySink {})));
+pub struct RbatisAuditPlugin {
+    pub table: Option<String>,
+    pub sink: Option<Box<dyn AuditSink>>,
+    /// the primary key column,default "id"
+    pub primary_key: String,
+    pub actor: Option<Box<dyn Fn() -> Option<String> + Send + Sync>>,
+}
+
+impl Debug for RbatisAuditPlugin {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RbatisAuditPlugin")
+            .field("table", &self.table)
+            .field("sink", &self.sink)
+            .field("primary_key", &self.primary_key)
+            .finish()
+    }
+}
+
+impl RbatisAuditPlugin {
+    pub fn table(table: &str) -> Self {
+        Self {
+            table: Some(table.to_string()),
+            sink: None,
+            primary_key: "id".to_string(),
+            actor: None,
+        }
+    }
+
+    pub fn sink(sink: impl AuditSink + 'static) -> Self {
+        Self {
+            table: None,
+            sink: Some(Box::new(sink)),
+            primary_key: "id".to_string(),
+            actor: None,
+        }
+    }
+
+    pub fn primary_key(mut self, column: &str) -> Self {
+        self.primary_key = column.to_string();
+        self
+    }
+
+    pub fn actor(mut self, f: impl Fn() -> Option<String> + Send + Sync + 'static) -> Self {
+        self.actor = Some(Box::new(f));
+        self
+    }
+}
+
+#[async_trait]
+impl AuditPlugin for RbatisAuditPlugin {
+    fn primary_key(&self, _table: &str) -> String {
+        self.primary_key.clone()
+    }
+
+    fn actor(&self) -> Option<String> {
+        self.actor.as_ref().and_then(|f| f())
+    }
+
+    fn audit_table(&self) -> Option<&str> {
+        self.table.as_deref()
+    }
+
+    async fn sink(&self, rb: &Rbatis, records: Vec<AuditRecord>) -> Result<(), Error> {
+        match &self.sink {
+            Some(sink) => sink.write(rb, records).await,
+            None => Ok(()),
+        }
+    }
+}
+
+/// records of the inserted rows,last_insert_id is used if the primary key of single row is null
+pub fn make_insert_records(plugin: &dyn AuditPlugin, table: &str, rows: Vec<Value>, last_insert_id: Option<i64>) -> Vec<AuditRecord> {
+    let primary_key = plugin.primary_key(table);
+    let single = rows.len() == 1;
+    let actor = plugin.actor();
+    let timestamp = Utc::now();
+    rows.into_iter()
+        .map(|row| {
+            let mut key = row.get(&primary_key).cloned().unwrap_or(Value::Null);
+            if key.is_null() && single {
+                key = last_insert_id.map(Value::from).unwrap_or(Value::Null);
+            }
+            AuditRecord {
+                table: table.to_string(),
+                primary_key: key,
+                operation: AuditOperation::Insert,
+                old: None,
+                new: Some(row),
+                actor: actor.clone(),
+                timestamp,
+            }
+        })
+        .collect()
+}
+
+/// records of the updated rows,olds are the stored rows before update,sets are the new values of columns.
+/// only the changed columns are recorded,the row not changed is skipped
+pub fn make_update_records(plugin: &dyn AuditPlugin, table: &str, olds: Vec<Value>, sets: &Map<String, Value>) -> Vec<AuditRecord> {
+    let primary_key = plugin.primary_key(table);
+    let actor = plugin.actor();
+    let timestamp = Utc::now();
+    let mut records = vec![];
+    for row in olds {
+        let old_row = match row {
+            Value::Object(m) => m,
+            _ => continue,
+        };
+        let (old, new) = match diff(&old_row, sets) {
+            Some(v) => v,
+            None => continue,
+        };
+        records.push(AuditRecord {
+            table: table.to_string(),
+            primary_key: old_row.get(&primary_key).cloned().unwrap_or(Value::Null),
+            operation: AuditOperation::Update,
+            old: Some(old),
+            new: Some(new),
+            actor: actor.clone(),
+            timestamp,
+        });
+    }
+    records
+}
+
+/// records of the removed rows,olds are the stored rows before remove
+pub fn make_delete_records(plugin: &dyn AuditPlugin, table: &str, olds: Vec<Value>) -> Vec<AuditRecord> {
+    let primary_key = plugin.primary_key(table);
+    let actor = plugin.actor();
+    let timestamp = Utc::now();
+    olds.into_iter()
+        .map(|row| AuditRecord {
+            table: table.to_string(),
+            primary_key: row.get(&primary_key).cloned().unwrap_or(Value::Null),
+            operation: AuditOperation::Delete,
+            old: Some(row),
+            new: None,
+            actor: actor.clone(),
+            timestamp,
+        })
+        .collect()
+}
+
+/// replace the values of sensitive columns(CRUDTable::sensitive_columns()) in old/new with "******"
+pub fn mask_records(records: &mut [AuditRecord], columns: &[String]) {
+    if columns.is_empty() {
+        return;
+    }
+    for x in records {
+        for row in [&mut x.old, &mut x.new] {
+            if let Some(Value::Object(m)) = row {
+                for (column, value) in m.iter_mut() {
+                    if columns.iter().any(|c| c.eq_ignore_ascii_case(column)) && !value.is_null() {
+                        *value = Value::String("******".to_string());
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// the changed columns of new,return (old values,new values),None if nothing changed
+pub fn diff(old: &Map<String, Value>, new: &Map<String, Value>) -> Option<(Value, Value)> {
+    let mut old_values = Map::new();
+    let mut new_values = Map::new();
+    for (column, value) in new {
+        let stored = old.get(column).unwrap_or(&Value::Null);
+        if stored != value {
+            old_values.insert(column.clone(), stored.clone());
+            new_values.insert(column.clone(), value.clone());
+        }
+    }
+    if new_values.is_empty() {
+        return None;
+    }
+    Some((Value::Object(old_values), Value::Object(new_values)))
+}
+
+/// the insert sql of records into the audit table
+pub fn make_audit_sql(driver_type: &DriverType, audit_table: &str, records: &[AuditRecord]) -> (String, Vec<Value>) {
+    let mut values = vec![];
+    let mut args = vec![];
+    for x in records {
+        let row_args = vec![
+            Value::String(x.table.clone()),
+            to_string_value(&x.primary_key),
+            Value::String(x.operation.as_str().to_string()),
+            x.old.as_ref().map(|v| Value::String(v.to_string())).unwrap_or(Value::Null),
+            x.new.as_ref().map(|v| Value::String(v.to_string())).unwrap_or(Value::Null),
+            x.actor.clone().map(Value::String).unwrap_or(Value::Null),
+            Value::String(x.timestamp.format("%Y-%m-%d %H:%M:%S%.3f").to_string()),
+        ];
+        let mut placeholders = vec![];
+        for arg in row_args {
+            let mut data = String::new();
+            driver_type.stmt_convert(args.len(), &mut data);
+            placeholders.push(data);
+            args.push(arg);
+        }
+        values.push(format!("({})", placeholders.join(",")));
+    }
+    let sql = format!(
+        "{} {} (table_name,primary_key,operation,old_value,new_value,actor,create_time) {} {}",
+        crate::sql::TEMPLATE.insert_into.value,
+        audit_table,
+        crate::sql::TEMPLATE.values.value,
+        values.join(",")
+    );
+    (sql, args)
+}
+
+fn to_string_value(arg: &Value) -> Value {
+    match arg {
+        Value::Null => Value::Null,
+        Value::String(_) => arg.clone(),
+        _ => Value::String(arg.to_string()),
+    }
+}
@@ -0,0 +1,491 @@
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+
+use async_trait::async_trait;
+use serde_json::Value;
+
+use crate::core::db::DriverType;
+use crate::core::Error;
+use crate::plugin::intercept::{Intercept, InterceptContext, SqlOperation};
+use crate::rbatis::Rbatis;
+use crate::sql::lexer::{tokenize, Token};
+
+/// the rule of SqlFirewall
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum FirewallRule {
+    /// update/delete without where
+    NoWhere,
+    /// where clause is always true,for example: where 1 = 1,where 'a' = 'a' or id = ?
+    Tautology,
+    /// more than one statement,for example: select 1;drop table a
+    MultiStatement,
+    /// create/alter/drop/truncate/rename/grant/revoke
+    Ddl,
+    /// select the big table without limit,
+    /// skip the aggregate only select(for example: select count(1) from a) and SqlOperation::PageCount/Aggregate
+    SelectWithoutLimit,
+    /// the table is in deny_tables or not in allow_tables
+    TableDenied,
+}
+
+/// an sql break the rule
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FirewallViolation {
+    pub rule: FirewallRule,
+    pub table: Option<String>,
+}
+
+impl Display for FirewallViolation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.table {
+            Some(table) => write!(f, "{:?}(table:{})", self.rule, table),
+            None => write!(f, "{:?}", self.rule),
+        }
+    }
+}
+
+/// rules based sql firewall,parse the sql and reject(or only log in audit mode) the dangerous sql
+/// for example:
+///     rb.add_intercept(SqlFirewall::default()
+///         .big_table("biz_log")
+///         .deny_table("sys_secret")
+///         .audit_only(false));
+#[derive(Clone, Debug)]
+pub struct SqlFirewall {
+    /// the enabled rules,default all
+    pub rules: HashSet<FirewallRule>,
+    /// only log the violations(warn),not reject the sql
+    pub audit_only: bool,
+    /// the tables must select with limit
+    pub big_tables: HashSet<String>,
+    /// if not empty,only these tables can be used
+    pub allow_tables: HashSet<String>,
+    /// these tables can not be used
+    pub deny_tables: HashSet<String>,
+}
+
+impl Default for SqlFirewall {
+    fn default() -> Self {
+        Self {
+            rules: vec![
+                FirewallRule::NoWhere,
+                FirewallRule::Tautology,
+                FirewallRule::MultiStatement,
+                FirewallRule::Ddl,
+                FirewallRule::SelectWithoutLimit,
+                FirewallRule::TableDenied,
+            ].into_iter().collect(),
+            audit_only: false,
+            big_tables: HashSet::new(),
+            allow_tables: HashSet::new(),
+            deny_tables: HashSet::new(),
+        }
+    }
+}
+
+impl SqlFirewall {
+    pub fn audit_only(mut self, audit_only: bool) -> Self {
+        self.audit_only = audit_only;
+        self
+    }
+
+    /// disable an rule
+    pub fn allow(mut self, rule: FirewallRule) -> Self {
+        self.rules.remove(&rule);
+        self
+    }
+
+    pub fn big_table(mut self, table: &str) -> Self {
+        self.big_tables.insert(table.to_lowercase());
+        self
+    }
+
+    pub fn allow_table(mut self, table: &str) -> Self {
+        self.allow_tables.insert(table.to_lowercase());
+        self
+    }
+
+    pub fn deny_table(mut self, table: &str) -> Self {
+        self.deny_tables.insert(table.to_lowercase());
+        self
+    }
+
+    /// check the sql,return the violations
+    pub fn check(&self, driver_type: &DriverType, context: &InterceptContext, sql: &str) -> Vec<FirewallViolation> {
+        let mut violations = vec![];
+        let mut push = |rule: FirewallRule, table: Option<String>| {
+            if self.rules.contains(&rule) {
+                let v = FirewallViolation { rule, table };
+                if !violations.contains(&v) {
+                    violations.push(v);
+                }
+            }
+        };
+        let tokens = tokenize(driver_type, sql);
+        let statements: Vec<&[Token]> = tokens
+            .split(|x| *x == Token::Punct(';'))
+            .filter(|x| !x.is_empty())
+            .collect();
+        if statements.len() > 1 {
+            push(FirewallRule::MultiStatement, None);
+        }
+        for statement in statements {
+            let head = statement[0].name().unwrap_or_default();
+            let mut tables = statement_tables(statement);
+            if let Some(table) = &context.table {
+                if !tables.contains(&table.to_lowercase()) {
+                    tables.push(table.to_lowercase());
+                }
+            }
+            for table in &tables {
+                if self.deny_tables.contains(table) || (!self.allow_tables.is_empty() && !self.allow_tables.contains(table)) {
+                    push(FirewallRule::TableDenied, Some(table.clone()));
+                }
+            }
+            match head {
+                "create" | "alter" | "drop" | "truncate" | "rename" | "grant" | "revoke" => {
+                    push(FirewallRule::Ddl, tables.first().cloned());
+                }
+                "update" | "delete" => {
+                    match find_word(statement, "where") {
+                        None => push(FirewallRule::NoWhere, tables.first().cloned()),
+                        Some(index) => {
+                            if is_tautology(&where_clause(&statement[index + 1..])) {
+                                push(FirewallRule::Tautology, tables.first().cloned());
+                            }
+                        }
+                    }
+                }
+                "select" | "with" => {
+                    if let Some(index) = find_word(statement, "where") {
+                        if is_tautology(&where_clause(&statement[index + 1..])) {
+                            push(FirewallRule::Tautology, tables.first().cloned());
+                        }
+                    }
+                    let have_limit = ["limit", "top", "fetch"].iter().any(|x| find_word(statement, x).is_some());
+                    let is_aggregate = context.operation == SqlOperation::PageCount
+                        || context.operation == SqlOperation::Aggregate
+                        || is_aggregate_only(statement);
+                    if !have_limit && !is_aggregate {
+                        for table in &tables {
+                            if self.big_tables.contains(table) {
+                                push(FirewallRule::SelectWithoutLimit, Some(table.clone()));
+                            }
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        violations
+    }
+}
+
+#[async_trait]
+impl Intercept for SqlFirewall {
+    async fn before(
+        &self,
+        rb: &Rbatis,
+        context: &InterceptContext,
+        sql: &mut String,
+        _args: &mut Vec<Value>,
+    ) -> Result<(), Error> {
+        let driver_type = rb.driver_type().unwrap_or(DriverType::None);
+        let violations = self.check(&driver_type, context, sql);
+        if violations.is_empty() {
+            return Ok(());
+        }
+        let names: Vec<String> = violations.iter().map(|x| x.to_string()).collect();
+        let message = format!("[rbatis][SqlFirewall] {} sql:{}", names.join(","), sql);
+        if self.audit_only {
+            rb.log_plugin.warn(&message);
+            return Ok(());
+        }
+        Err(Error::from(message))
+    }
+}
+
+/// the index of top level word
+fn find_word(tokens: &[Token], word: &str) -> Option<usize> {
+    let mut depth = 0;
+    for (index, token) in tokens.iter().enumerate() {
+        match token {
+            Token::Punct('(') => depth += 1,
+            Token::Punct(')') => depth -= 1,
+            _ => {
+                if depth == 0 && token.is_word(word) {
+                    return Some(index);
+                }
+            }
+        }
+    }
+    None
+}
+
+/// the select list only have aggregate functions and no group by,the result is one row.
+/// for example: select count(1),max(id) as m from a where status = ?
+fn is_aggregate_only(tokens: &[Token]) -> bool {
+    let select = match find_word(tokens, "select") {
+        Some(v) => v,
+        None => return false,
+    };
+    if find_word(tokens, "group").is_some() {
+        return false;
+    }
+    let from = find_word(tokens, "from").unwrap_or(tokens.len());
+    if from <= select + 1 {
+        return false;
+    }
+    split_punct(&tokens[select + 1..from], ',').iter().all(|item| match item {
+        [Token::Word(function), Token::Punct('('), rest @ ..] => {
+            if !["count", "sum", "avg", "min", "max"].contains(&function.as_str()) {
+                return false;
+            }
+            //the tokens after 'count(..)' can only be the alias
+            let mut depth = 1;
+            let mut end = None;
+            for (index, token) in rest.iter().enumerate() {
+                match token {
+                    Token::Punct('(') => depth += 1,
+                    Token::Punct(')') => {
+                        depth -= 1;
+                        if depth == 0 {
+                            end = Some(index);
+                            break;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            match end.map(|x| &rest[x + 1..]) {
+                Some([]) => true,
+                Some([alias]) => alias.name().is_some(),
+                Some([r#as, alias]) => r#as.is_word("as") && alias.name().is_some(),
+                _ => false,
+            }
+        }
+        _ => false,
+    })
+}
+
+/// split the tokens by top level punct
+fn split_punct(tokens: &[Token], punct: char) -> Vec<&[Token]> {
+    let mut parts = vec![];
+    let mut depth = 0;
+    let mut start = 0;
+    for (index, token) in tokens.iter().enumerate() {
+        match token {
+            Token::Punct('(') => depth += 1,
+            Token::Punct(')') => depth -= 1,
+            Token::Punct(c) if *c == punct && depth == 0 => {
+                parts.push(&tokens[start..index]);
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&tokens[start..]);
+    parts
+}
+
+/// the tables after from/join/update/into/table
+fn statement_tables(tokens: &[Token]) -> Vec<String> {
+    let mut tables = vec![];
+    //the word before every open '(',for example: extract(year from a)
+    let mut parens: Vec<Option<&str>> = vec![];
+    for (index, token) in tokens.iter().enumerate() {
+        match token {
+            Token::Punct('(') => {
+                parens.push(index.checked_sub(1).and_then(|x| tokens[x].name()));
+                continue;
+            }
+            Token::Punct(')') => {
+                parens.pop();
+                continue;
+            }
+            _ => {}
+        }
+        let is_table_word = ["from", "join", "update", "into", "table"].iter().any(|x| token.is_word(x));
+        if !is_table_word {
+            continue;
+        }
+        if let Some(Some(function)) = parens.last() {
+            if ["extract", "substring", "trim", "position", "overlay"].contains(function) {
+                continue;
+            }
+        }
+        //on duplicate key update
+        if token.is_word("update") && index > 0 && tokens[index - 1].is_word("key") {
+            continue;
+        }
+        //schema.table,skip 'if not exists','only'
+        let mut i = index + 1;
+        while tokens.get(i).map(|x| ["if", "not", "exists", "only"].iter().any(|w| x.is_word(w))).unwrap_or(false) {
+            i += 1;
+        }
+        let mut name = None;
+        while let Some(x) = tokens.get(i).and_then(|x| x.name()) {
+            name = Some(x.to_string());
+            if tokens.get(i + 1) == Some(&Token::Punct('.')) {
+                i += 2;
+            } else {
+                break;
+            }
+        }
+        if let Some(name) = name {
+            if name != "select" && !tables.contains(&name) {
+                tables.push(name);
+            }
+        }
+    }
+    tables
+}
+
+/// the tokens of where clause,end with group/order/limit/returning...
+fn where_clause(tokens: &[Token]) -> Vec<Token> {
+    let mut depth = 0;
+    let mut result = vec![];
+    for token in tokens {
+        match token {
+            Token::Punct('(') => depth += 1,
+            Token::Punct(')') => depth -= 1,
+            _ => {
+                let is_end = ["group", "order", "limit", "offset", "returning", "having", "for", "union", "fetch"]
+                    .iter()
+                    .any(|x| token.is_word(x));
+                if depth == 0 && is_end {
+                    break;
+                }
+            }
+        }
+        result.push(token.clone());
+    }
+    result
+}
+
+/// split the tokens by top level word
+fn split_word<'a>(tokens: &'a [Token], word: &str) -> Vec<&'a [Token]> {
+    let mut parts = vec![];
+    let mut depth = 0;
+    let mut start = 0;
+    for (index, token) in tokens.iter().enumerate() {
+        match token {
+            Token::Punct('(') => depth += 1,
+            Token::Punct(')') => depth -= 1,
+            _ => {
+                if depth == 0 && token.is_word(word) {
+                    parts.push(&tokens[start..index]);
+                    start = index + 1;
+                }
+            }
+        }
+    }
+    parts.push(&tokens[start..]);
+    parts
+}
+
+/// is the condition always true
+fn is_tautology(tokens: &[Token]) -> bool {
+    let tokens = strip_parens(tokens);
+    if tokens.is_empty() {
+        return false;
+    }
+    //'a or b' is true if a or b is true
+    let or = split_word(tokens, "or");
+    if or.len() > 1 {
+        return or.iter().any(|x| is_tautology(x));
+    }
+    //'a and b' is true if a and b are true
+    let and = split_word(tokens, "and");
+    if and.len() > 1 {
+        //'x between a and b' is split by 'and'
+        if find_word(tokens, "between").is_some() {
+            return false;
+        }
+        return and.iter().all(|x| is_tautology(x));
+    }
+    match tokens {
+        [x] => is_true_literal(x),
+        [Token::Word(not), x] if not == "not" => is_false_literal(x),
+        [left, Token::Op(op), right] => compare(left, op, right).unwrap_or(false),
+        [left, Token::Word(is), Token::Word(not), Token::Word(null)] if is == "is" && not == "not" && null == "null" => {
+            is_literal(left)
+        }
+        [left, Token::Word(like), Token::Str(pattern)] if like == "like" => {
+            !pattern.is_empty() && pattern.chars().all(|x| x == '%')
+        }
+        _ => false,
+    }
+}
+
+/// remove the outer '(..)'
+fn strip_parens(tokens: &[Token]) -> &[Token] {
+    let mut tokens = tokens;
+    while tokens.len() >= 2 && tokens[0] == Token::Punct('(') && tokens[tokens.len() - 1] == Token::Punct(')') {
+        //the first '(' must close at the end
+        let mut depth = 0;
+        let mut close_at_end = true;
+        for (index, token) in tokens.iter().enumerate() {
+            match token {
+                Token::Punct('(') => depth += 1,
+                Token::Punct(')') => {
+                    depth -= 1;
+                    if depth == 0 && index != tokens.len() - 1 {
+                        close_at_end = false;
+                        break;
+                    }
+                }
+                _ => {}
+            }
+        }
+        if !close_at_end {
+            break;
+        }
+        tokens = &tokens[1..tokens.len() - 1];
+    }
+    tokens
+}
+
+fn is_literal(token: &Token) -> bool {
+    match token {
+        Token::Number(_) | Token::Str(_) => true,
+        Token::Word(w) => w == "true" || w == "false",
+        _ => false,
+    }
+}
+
+fn is_true_literal(token: &Token) -> bool {
+    match token {
+        Token::Number(n) => n.parse::<f64>().map(|x| x != 0.0).unwrap_or(false),
+        Token::Word(w) => w == "true",
+        _ => false,
+    }
+}
+
+fn is_false_literal(token: &Token) -> bool {
+    match token {
+        Token::Number(n) => n.parse::<f64>().map(|x| x == 0.0).unwrap_or(false),
+        Token::Word(w) => w == "false",
+        _ => false,
+    }
+}
+
+/// compare two literals(or the same column),None if can not know
+fn compare(left: &Token, op: &str, right: &Token) -> Option<bool> {
+    let ordering = match (left, right) {
+        (Token::Number(a), Token::Number(b)) => a.parse::<f64>().ok()?.partial_cmp(&b.parse::<f64>().ok()?)?,
+        (Token::Str(a), Token::Str(b)) => a.cmp(b),
+        //column = column
+        (Token::Word(a), Token::Word(b)) | (Token::Ident(a), Token::Ident(b)) if a == b && a != "null" => std::cmp::Ordering::Equal,
+        _ => return None,
+    };
+    Some(match op {
+        "=" | "==" => ordering.is_eq(),
+        "<>" | "!=" => ordering.is_ne(),
+        ">" => ordering.is_gt(),
+        ">=" => ordering.is_ge(),
+        "<" => ordering.is_lt(),
+        "<=" => ordering.is_le(),
+        _ => return None,
+    })
+}
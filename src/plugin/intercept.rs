@@ -1,10 +1,114 @@
-use crate::core::convert::StmtConvert;
+use crate::core::db::DBExecResult;
 use crate::crud::CRUDTable;
 use crate::rbatis::Rbatis;
+use crate::sql::format_sql;
 use crate::DriverType;
+use async_trait::async_trait;
 use rbatis_core::Error;
 use serde_json::Value;
 use std::fmt::{Debug, Display};
+use std::time::Duration;
+
+/// the kind of sql call
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SqlOperation {
+    Fetch,
+    Exec,
+    /// the count sql of fetch_page
+    PageCount,
+    /// the count/aggregate sql of CRUD,for example fetch_count_by_wrapper(),fetch_aggregate(),fetch_group_aggregate()
+    Aggregate,
+}
+
+/// context of an sql call,see Intercept
+#[derive(Clone, Debug)]
+pub struct InterceptContext {
+    pub operation: SqlOperation,
+    /// the table name if known,for example the CRUD methods
+    pub table: Option<String>,
+    /// is run in RBatisTxExecutor
+    pub in_transaction: bool,
+    /// is prepared sql(has args)
+    pub is_prepared: bool,
+}
+
+/// output of an sql call
+#[derive(Debug)]
+pub enum SqlOutput<'a> {
+    Exec(&'a DBExecResult),
+    /// rows count of fetch
+    Rows(usize),
+}
+
+/// result of an sql call,see Intercept::after()
+#[derive(Debug)]
+pub struct InterceptResult<'a> {
+    pub output: Result<SqlOutput<'a>, &'a Error>,
+    /// time of execute,not include intercepts
+    pub elapsed: Duration,
+}
+
+/// async sql intercept,RBatisConnExecutor/RBatisTxExecutor call before() then execute then after().
+/// Rbatis.sql_intercepts run as the before() at first of the chain
+/// for example:
+///     #[derive(Debug)]
+///     pub struct TimeIntercept {}
+///     #[async_trait]
+///     impl Intercept for TimeIntercept {
+///         async fn after(&self, rb: &Rbatis, context: &InterceptContext, sql: &str, args: &Vec<Value>, result: &InterceptResult<'_>) -> Result<(), Error> {
+///             println!("{:?} {} use {:?}", context.operation, sql, result.elapsed);
+///             Ok(())
+///         }
+///     }
+///     rb.add_intercept(TimeIntercept {});
+#[async_trait]
+pub trait Intercept: Send + Sync + Debug {
+    ///the name
+    fn name(&self) -> &str {
+        std::any::type_name::<Self>()
+    }
+
+    /// before execute,can change sql/args,return Err will stop the execute
+    async fn before(
+        &self,
+        _rb: &Rbatis,
+        _context: &InterceptContext,
+        _sql: &mut String,
+        _args: &mut Vec<Value>,
+    ) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// after execute(also when execute fail),return Err will replace the result with it
+    async fn after(
+        &self,
+        _rb: &Rbatis,
+        _context: &InterceptContext,
+        _sql: &str,
+        _args: &Vec<Value>,
+        _result: &InterceptResult<'_>,
+    ) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+/// SqlIntercept in the Intercept chain
+#[async_trait]
+impl Intercept for Box<dyn SqlIntercept> {
+    fn name(&self) -> &str {
+        self.as_ref().name()
+    }
+
+    async fn before(
+        &self,
+        rb: &Rbatis,
+        context: &InterceptContext,
+        sql: &mut String,
+        args: &mut Vec<Value>,
+    ) -> Result<(), Error> {
+        self.do_intercept(rb, sql, args, context.is_prepared)
+    }
+}
 
 /// sql intercept
 pub trait SqlIntercept: Send + Sync + Debug {
@@ -23,6 +127,7 @@ pub trait SqlIntercept: Send + Sync + Debug {
     ) -> Result<(), crate::core::Error>;
 }
 
+/// log the sql with inline args(see crate::sql::format_sql),the args are redacted by the log plugin
 #[derive(Debug)]
 pub struct RbatisLogFormatSqlIntercept {}
 
@@ -38,24 +143,19 @@ impl SqlIntercept for RbatisLogFormatSqlIntercept {
         match driver_type {
             DriverType::None => {}
             DriverType::Mysql | DriverType::Postgres | DriverType::Sqlite | DriverType::Mssql => {
-                let mut formated = format!("[format_sql]{}", sql);
-                for index in 0..args.len() {
-                    let mut data = String::new();
-                    driver_type.stmt_convert(index, &mut data);
-                    formated = formated.replacen(
-                        &data,
-                        &format!("{}", args.get(index).unwrap()),
-                        1,
-                    );
-                }
-                rb.log_plugin.info(&formated);
+                let log_args = match rb.log_plugin.redact_policy() {
+                    Some(policy) => policy.redact_args(&driver_type, sql, args),
+                    None => args.clone(),
+                };
+                rb.log_plugin.info(&format!("[format_sql]{}", format_sql(&driver_type, sql, &log_args)));
             }
         }
         return Ok(());
     }
 }
 
-/// Prevent full table updates and deletions
+/// Prevent full table updates and deletions,only check the ' where ',
+/// see crate::plugin::firewall::SqlFirewall for the rules based check
 #[derive(Debug)]
 pub struct BlockAttackDeleteInterceptor {}
 
@@ -80,7 +180,8 @@ impl SqlIntercept for BlockAttackDeleteInterceptor {
     }
 }
 
-/// Prevent full table updates and deletions
+/// Prevent full table updates and deletions,only check the ' where ',
+/// see crate::plugin::firewall::SqlFirewall for the rules based check
 #[derive(Debug)]
 pub struct BlockAttackUpdateInterceptor {}
 
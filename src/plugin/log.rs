@@ -1,8 +1,97 @@
+use std::io::Write;
 use std::ops::Deref;
+use std::sync::Mutex;
+use std::time::Duration;
 
 use log::{debug, error, info, trace, warn, LevelFilter};
+use serde_json::Value;
 use std::fmt::{Debug, Display};
 
+use crate::core::Error;
+use crate::plugin::intercept::SqlOperation;
+use crate::plugin::redact::RedactPolicy;
+use crate::utils::string_util;
+
+/// the phase of an sql log
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SqlLogPhase {
+    /// before execute
+    Request,
+    /// after execute,have rows_affected/elapsed or error
+    Response,
+}
+
+/// an structured sql log,RBatisConnExecutor/RBatisTxExecutor send it to LogPlugin::log_sql()
+#[derive(Clone, Debug)]
+pub struct SqlLogEvent<'a> {
+    pub phase: SqlLogPhase,
+    pub operation: SqlOperation,
+    pub sql: &'a str,
+    pub args: &'a [Value],
+    /// rows affected(exec) or rows returned(fetch),only Response
+    pub rows_affected: Option<u64>,
+    /// time of execute,only Response
+    pub elapsed: Option<Duration>,
+    pub error: Option<&'a Error>,
+    /// the id of RBatisTxExecutor,None if not in transaction
+    pub tx_id: Option<u64>,
+}
+
+impl SqlLogEvent<'_> {
+    /// the json of event,for example:
+    /// {"phase":"response","operation":"fetch","sql":"select * from a where id = ?","args":[1],"rows_affected":1,"elapsed_us":523,"error":null,"tx_id":null}
+    pub fn to_json(&self) -> Value {
+        json!({
+            "phase": match self.phase {
+                SqlLogPhase::Request => "request",
+                SqlLogPhase::Response => "response",
+            },
+            "operation": match self.operation {
+                SqlOperation::Fetch => "fetch",
+                SqlOperation::Exec => "exec",
+                SqlOperation::PageCount => "page_count",
+                SqlOperation::Aggregate => "aggregate",
+            },
+            "sql": self.sql,
+            "args": self.args,
+            "rows_affected": self.rows_affected,
+            "elapsed_us": self.elapsed.map(|x| x.as_micros() as u64),
+            "error": self.error.map(|x| x.to_string()),
+            "tx_id": self.tx_id,
+        })
+    }
+
+    /// the human-readable text of event,for example:
+    ///   Exec   ==> update a set name = ? where id = ?
+    ///   [rbatis] [tx:1] Args   ==> ["a",1]
+    pub fn to_text(&self) -> String {
+        match self.phase {
+            SqlLogPhase::Request => {
+                let head = match self.operation {
+                    SqlOperation::Exec => "Exec  ",
+                    _ => "Fetch ",
+                };
+                format!(
+                    "{} ==> {}\n{}[rbatis] [{}] Args   ==> {}",
+                    head,
+                    self.sql,
+                    string_util::LOG_SPACE,
+                    self.tx_id.map(|x| format!("tx:{}", x)).unwrap_or_default(),
+                    Value::Array(self.args.to_vec())
+                )
+            }
+            SqlLogPhase::Response => {
+                let elapsed = self.elapsed.map(|x| format!(" ({:?})", x)).unwrap_or_default();
+                match (self.error, self.operation) {
+                    (Some(e), _) => format!("ReturnErr  <== {}{}", e, elapsed),
+                    (None, SqlOperation::Exec) => format!("RowsAffected <== {}{}", self.rows_affected.unwrap_or(0), elapsed),
+                    (None, _) => format!("ReturnRows <== {}{}", self.rows_affected.unwrap_or(0), elapsed),
+                }
+            }
+        }
+    }
+}
+
 /// log plugin
 pub trait LogPlugin: Send + Sync + Debug {
     ///the name
@@ -10,9 +99,23 @@ pub trait LogPlugin: Send + Sync + Debug {
         std::any::type_name::<Self>()
     }
     fn get_level_filter(&self) -> &LevelFilter;
+
+    /// the redaction policy of args,SqlLogEvent.args are redacted by it
+    fn redact_policy(&self) -> Option<&RedactPolicy> {
+        None
+    }
     fn is_enable(&self) -> bool {
         return !self.get_level_filter().eq(&log::LevelFilter::Off);
     }
+    /// log an sql event,default log the to_text() with info level(error level if have error)
+    fn log_sql(&self, event: &SqlLogEvent<'_>) {
+        if event.error.is_some() {
+            self.error(&event.to_text());
+        } else {
+            self.info(&event.to_text());
+        }
+    }
+
     fn do_log(&self, data: &str) {
         match self.get_level_filter() {
             log::LevelFilter::Error => {
@@ -88,18 +191,91 @@ pub trait LogPlugin: Send + Sync + Debug {
 #[derive(Debug)]
 pub struct RbatisLogPlugin {
     pub level_filter: LevelFilter,
+    pub redact_policy: Option<RedactPolicy>,
 }
 
 impl Default for RbatisLogPlugin {
     fn default() -> Self {
         Self {
             level_filter: log::LevelFilter::Info,
+            redact_policy: None,
         }
     }
 }
 
+impl RbatisLogPlugin {
+    pub fn redact(mut self, policy: RedactPolicy) -> Self {
+        self.redact_policy = Some(policy);
+        self
+    }
+}
+
 impl LogPlugin for RbatisLogPlugin {
     fn get_level_filter(&self) -> &LevelFilter {
         &self.level_filter
     }
+
+    fn redact_policy(&self) -> Option<&RedactPolicy> {
+        self.redact_policy.as_ref()
+    }
+}
+
+/// log sql event as JSON lines,one event one line,
+/// the other logs(not sql event) still write to the 'log' crate
+/// for example:
+///     rb.set_log_plugin(JsonLogPlugin::new(std::fs::File::create("sql.log").unwrap()));
+#[derive(Debug)]
+pub struct JsonLogPlugin {
+    pub level_filter: LevelFilter,
+    pub redact_policy: Option<RedactPolicy>,
+    writer: Mutex<JsonWriter>,
+}
+
+struct JsonWriter(Box<dyn Write + Send>);
+
+impl Debug for JsonWriter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("JsonWriter")
+    }
+}
+
+impl Default for JsonLogPlugin {
+    fn default() -> Self {
+        Self::new(std::io::stdout())
+    }
+}
+
+impl JsonLogPlugin {
+    pub fn new(writer: impl Write + Send + 'static) -> Self {
+        Self {
+            level_filter: log::LevelFilter::Info,
+            redact_policy: None,
+            writer: Mutex::new(JsonWriter(Box::new(writer))),
+        }
+    }
+
+    pub fn redact(mut self, policy: RedactPolicy) -> Self {
+        self.redact_policy = Some(policy);
+        self
+    }
+}
+
+impl LogPlugin for JsonLogPlugin {
+    fn get_level_filter(&self) -> &LevelFilter {
+        &self.level_filter
+    }
+
+    fn redact_policy(&self) -> Option<&RedactPolicy> {
+        self.redact_policy.as_ref()
+    }
+
+    fn log_sql(&self, event: &SqlLogEvent<'_>) {
+        let level = if event.error.is_some() { LevelFilter::Error } else { LevelFilter::Info };
+        if self.level_filter.eq(&LevelFilter::Off) || self.level_filter.lt(&level) {
+            return;
+        }
+        if let Ok(mut writer) = self.writer.lock() {
+            let _ = writeln!(writer.0, "{}", event.to_json());
+        }
+    }
 }
@@ -5,7 +5,11 @@ use serde_json::Value;
 use crate::core::db::DriverType;
 use crate::core::Error;
 use crate::sql::rule::SqlRule;
+use async_trait::async_trait;
+
 use crate::crud::{CRUDTable, Skip};
+use crate::executor::ExecutorMut;
+use crate::wrapper::Wrapper;
 use std::ops::{Deref, DerefMut};
 use std::collections::HashMap;
 use serde::{Serialize, Deserialize, Serializer, Deserializer};
@@ -145,12 +149,13 @@ impl<'de, T> Deserialize<'de> for TableNoLogic<T> where T: CRUDTable {
 }
 
 
+#[async_trait]
 impl<T> CRUDTable for TableNoLogic<T> where T: CRUDTable {
     fn is_use_plugin(plugin_name: &str) -> bool {
         if plugin_name.eq(std::any::type_name::<RbatisLogicDeletePlugin>()) {
             return false;
         }
-        return true;
+        return T::is_use_plugin(plugin_name);
     }
 
     fn table_name() -> String {
@@ -165,6 +170,10 @@ impl<T> CRUDTable for TableNoLogic<T> where T: CRUDTable {
         T::formats(driver_type)
     }
 
+    fn sensitive_columns() -> Vec<String> {
+        T::sensitive_columns()
+    }
+
     fn make_value_sql_arg(
         &self,
         db_type: &DriverType,
@@ -173,6 +182,48 @@ impl<T> CRUDTable for TableNoLogic<T> where T: CRUDTable {
     ) -> crate::Result<(String, String, Vec<serde_json::Value>)> {
         T::make_value_sql_arg(&self.table, db_type, index,skips)
     }
+
+    async fn before_insert<E>(&self, executor: &mut E) -> crate::Result<Option<Self>>
+        where
+            E: ExecutorMut + Send + ?Sized,
+    {
+        Ok(self.table.before_insert(executor).await?.map(|table| TableNoLogic { table }))
+    }
+
+    async fn after_insert<E>(&self, executor: &mut E) -> crate::Result<()>
+        where
+            E: ExecutorMut + Send + ?Sized,
+    {
+        self.table.after_insert(executor).await
+    }
+
+    async fn before_update<E>(&mut self, executor: &mut E) -> crate::Result<()>
+        where
+            E: ExecutorMut + Send + ?Sized,
+    {
+        self.table.before_update(executor).await
+    }
+
+    async fn after_update<E>(&mut self, executor: &mut E) -> crate::Result<()>
+        where
+            E: ExecutorMut + Send + ?Sized,
+    {
+        self.table.after_update(executor).await
+    }
+
+    async fn before_delete<E>(executor: &mut E, w: &Wrapper) -> crate::Result<()>
+        where
+            E: ExecutorMut + Send + ?Sized,
+    {
+        T::before_delete(executor, w).await
+    }
+
+    async fn after_fetch<E>(&mut self, executor: &mut E) -> crate::Result<()>
+        where
+            E: ExecutorMut + Send + ?Sized,
+    {
+        self.table.after_fetch(executor).await
+    }
 }
 
 impl<T> Deref for TableNoLogic<T> where T: CRUDTable {
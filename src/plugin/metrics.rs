@@ -0,0 +1,271 @@
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::plugin::intercept::InterceptContext;
+
+/// sql metrics plugin,RBatisConnExecutor/RBatisTxExecutor record every exec/fetch,
+/// Rbatis::acquire()/acquire_begin() record the pool wait time
+pub trait MetricsPlugin: Send + Sync + Debug {
+    ///the name
+    fn name(&self) -> &str {
+        std::any::type_name::<Self>()
+    }
+
+    /// record an sql call,rows is the rows returned(fetch) or affected(exec)
+    fn record_sql(&self, context: &InterceptContext, sql: &str, elapsed: Duration, rows: u64, is_error: bool);
+
+    /// record the wait time of acquire an connection from pool
+    fn record_acquire(&self, wait: Duration, is_error: bool);
+
+    /// snapshot of all metrics
+    fn snapshot(&self) -> MetricsSnapshot;
+}
+
+/// latency histogram,buckets is (upper bound seconds,cumulative count)
+#[derive(Clone, Debug, PartialEq)]
+pub struct Histogram {
+    pub buckets: Vec<(f64, u64)>,
+    pub count: u64,
+    /// sum seconds
+    pub sum: f64,
+}
+
+impl Histogram {
+    pub fn new(bounds: &[f64]) -> Self {
+        Self {
+            buckets: bounds.iter().map(|x| (*x, 0)).collect(),
+            count: 0,
+            sum: 0.0,
+        }
+    }
+
+    pub fn observe(&mut self, value: Duration) {
+        let seconds = value.as_secs_f64();
+        for (bound, count) in &mut self.buckets {
+            if seconds <= *bound {
+                *count += 1;
+            }
+        }
+        self.count += 1;
+        self.sum += seconds;
+    }
+}
+
+/// metrics of an sql fingerprint
+#[derive(Clone, Debug, PartialEq)]
+pub struct StatementMetrics {
+    pub fingerprint: String,
+    pub calls: u64,
+    pub errors: u64,
+    /// rows returned or affected
+    pub rows: u64,
+    pub latency: Histogram,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct MetricsSnapshot {
+    /// sort by fingerprint
+    pub statements: Vec<StatementMetrics>,
+    pub acquire_errors: u64,
+    pub acquire_wait: Histogram,
+}
+
+impl MetricsSnapshot {
+    /// prometheus text exposition format
+    pub fn to_prometheus(&self) -> String {
+        let mut text = String::new();
+        text.push_str("# HELP rbatis_sql_calls_total sql calls\n# TYPE rbatis_sql_calls_total counter\n");
+        for x in &self.statements {
+            text.push_str(&format!("rbatis_sql_calls_total{{sql=\"{}\"}} {}\n", escape_label(&x.fingerprint), x.calls));
+        }
+        text.push_str("# HELP rbatis_sql_errors_total sql errors\n# TYPE rbatis_sql_errors_total counter\n");
+        for x in &self.statements {
+            text.push_str(&format!("rbatis_sql_errors_total{{sql=\"{}\"}} {}\n", escape_label(&x.fingerprint), x.errors));
+        }
+        text.push_str("# HELP rbatis_sql_rows_total rows returned or affected\n# TYPE rbatis_sql_rows_total counter\n");
+        for x in &self.statements {
+            text.push_str(&format!("rbatis_sql_rows_total{{sql=\"{}\"}} {}\n", escape_label(&x.fingerprint), x.rows));
+        }
+        text.push_str("# HELP rbatis_sql_duration_seconds sql latency\n# TYPE rbatis_sql_duration_seconds histogram\n");
+        for x in &self.statements {
+            let label = format!("sql=\"{}\",", escape_label(&x.fingerprint));
+            push_histogram(&mut text, "rbatis_sql_duration_seconds", &label, &x.latency);
+        }
+        text.push_str("# HELP rbatis_pool_acquire_errors_total pool acquire errors\n# TYPE rbatis_pool_acquire_errors_total counter\n");
+        text.push_str(&format!("rbatis_pool_acquire_errors_total {}\n", self.acquire_errors));
+        text.push_str("# HELP rbatis_pool_acquire_seconds pool acquire wait time\n# TYPE rbatis_pool_acquire_seconds histogram\n");
+        push_histogram(&mut text, "rbatis_pool_acquire_seconds", "", &self.acquire_wait);
+        text
+    }
+}
+
+fn push_histogram(text: &mut String, name: &str, label: &str, histogram: &Histogram) {
+    for (bound, count) in &histogram.buckets {
+        text.push_str(&format!("{}_bucket{{{}le=\"{}\"}} {}\n", name, label, bound, count));
+    }
+    text.push_str(&format!("{}_bucket{{{}le=\"+Inf\"}} {}\n", name, label, histogram.count));
+    let label = label.trim_end_matches(',');
+    if label.is_empty() {
+        text.push_str(&format!("{}_sum {}\n{}_count {}\n", name, histogram.sum, name, histogram.count));
+    } else {
+        text.push_str(&format!("{}_sum{{{}}} {}\n{}_count{{{}}} {}\n", name, label, histogram.sum, name, label, histogram.count));
+    }
+}
+
+fn escape_label(arg: &str) -> String {
+    arg.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+/// the default metrics plugin,keep the metrics in memory
+/// for example:
+///     rb.set_metrics_plugin(Some(RbatisMetricsPlugin::default()));
+///     //...
+///     let text = rb.metrics().unwrap().to_prometheus();
+#[derive(Debug)]
+pub struct RbatisMetricsPlugin {
+    /// histogram upper bounds(seconds)
+    pub buckets: Vec<f64>,
+    /// max fingerprints,the others will be record as 'other'
+    pub max_statements: usize,
+    statements: Mutex<HashMap<String, StatementMetrics>>,
+    acquire: Mutex<(u64, Histogram)>,
+}
+
+impl Default for RbatisMetricsPlugin {
+    fn default() -> Self {
+        Self::new(&[0.001, 0.005, 0.01, 0.05, 0.1, 0.5, 1.0, 5.0])
+    }
+}
+
+impl RbatisMetricsPlugin {
+    pub fn new(buckets: &[f64]) -> Self {
+        Self {
+            buckets: buckets.to_vec(),
+            max_statements: 1000,
+            statements: Mutex::new(HashMap::new()),
+            acquire: Mutex::new((0, Histogram::new(buckets))),
+        }
+    }
+}
+
+impl MetricsPlugin for RbatisMetricsPlugin {
+    fn record_sql(&self, _context: &InterceptContext, sql: &str, elapsed: Duration, rows: u64, is_error: bool) {
+        let mut fingerprint = sql_fingerprint(sql);
+        if let Ok(mut statements) = self.statements.lock() {
+            if !statements.contains_key(&fingerprint) && statements.len() >= self.max_statements {
+                fingerprint = "other".to_string();
+            }
+            let buckets = &self.buckets;
+            let item = statements.entry(fingerprint.clone()).or_insert_with(|| StatementMetrics {
+                fingerprint,
+                calls: 0,
+                errors: 0,
+                rows: 0,
+                latency: Histogram::new(buckets),
+            });
+            item.calls += 1;
+            if is_error {
+                item.errors += 1;
+            }
+            item.rows += rows;
+            item.latency.observe(elapsed);
+        }
+    }
+
+    fn record_acquire(&self, wait: Duration, is_error: bool) {
+        if let Ok(mut acquire) = self.acquire.lock() {
+            if is_error {
+                acquire.0 += 1;
+            }
+            acquire.1.observe(wait);
+        }
+    }
+
+    fn snapshot(&self) -> MetricsSnapshot {
+        let mut statements: Vec<StatementMetrics> = match self.statements.lock() {
+            Ok(statements) => statements.values().cloned().collect(),
+            Err(_) => vec![],
+        };
+        statements.sort_by(|a, b| a.fingerprint.cmp(&b.fingerprint));
+        let (acquire_errors, acquire_wait) = match self.acquire.lock() {
+            Ok(acquire) => acquire.clone(),
+            Err(_) => (0, Histogram::new(&self.buckets)),
+        };
+        MetricsSnapshot {
+            statements,
+            acquire_errors,
+            acquire_wait,
+        }
+    }
+}
+
+/// normalize the sql,string/number literals and placeholders will be '?',
+/// lists of '?' will be collapsed,for example:
+///   "select * from a where id in ($1,$2, $3) and name = 'a'" -> "select * from a where id in(?) and name = ?"
+pub fn sql_fingerprint(sql: &str) -> String {
+    let chars: Vec<char> = sql.chars().collect();
+    let mut result = String::with_capacity(sql.len());
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let prev_is_word = result.chars().last().map(|x| x.is_alphanumeric() || x == '_').unwrap_or(false);
+        if c == '\'' {
+            //string literal,'' is escaped quote
+            i += 1;
+            while i < chars.len() {
+                if chars[i] == '\'' {
+                    if chars.get(i + 1) == Some(&'\'') {
+                        i += 2;
+                        continue;
+                    }
+                    break;
+                }
+                i += 1;
+            }
+            result.push('?');
+            i += 1;
+        } else if c == '"' || c == '`' {
+            //quoted identifier
+            result.push(c);
+            i += 1;
+            while i < chars.len() {
+                result.push(chars[i]);
+                i += 1;
+                if chars[i - 1] == c {
+                    break;
+                }
+            }
+        } else if (c.is_ascii_digit() && !prev_is_word)
+            || (c == '$' && chars.get(i + 1).map(|x| x.is_ascii_digit()).unwrap_or(false))
+            || (c == '@' && chars.get(i + 1) == Some(&'p') && chars.get(i + 2).map(|x| x.is_ascii_digit()).unwrap_or(false)) {
+            //number or placeholder
+            i += if c == '@' { 2 } else { 1 };
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+            result.push('?');
+        } else if c.is_whitespace() {
+            while i < chars.len() && chars[i].is_whitespace() {
+                i += 1;
+            }
+            result.push(' ');
+        } else {
+            result.extend(c.to_lowercase());
+            i += 1;
+        }
+    }
+    //remove spaces around ',' '(' and before ')'
+    for x in &[",", "("] {
+        result = result.replace(&format!(" {}", x), x).replace(&format!("{} ", x), x);
+    }
+    result = result.replace(" )", ")");
+    while result.contains("?,?") {
+        result = result.replace("?,?", "?");
+    }
+    while result.contains("(?),(?)") {
+        result = result.replace("(?),(?)", "(?)");
+    }
+    result.trim().to_string()
+}
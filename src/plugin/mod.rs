@@ -1,7 +1,14 @@
+pub mod audit;
+pub mod firewall;
 pub mod intercept;
 pub mod log;
 pub mod logic_delete;
+pub mod metrics;
 pub mod object_id;
 pub mod page;
+pub mod redact;
+pub mod slow_query;
 pub mod snowflake;
+#[cfg(feature = "tracing")]
+pub mod trace;
 pub mod version_lock;
\ No newline at end of file
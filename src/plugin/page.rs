@@ -13,6 +13,9 @@ use crate::sql::PageLimit;
 /// default 10
 pub const DEFAULT_PAGE_SIZE: u64 = 10;
 
+/// default max page size 1000,used by PageRequest::new_option() to clamp the page_size from clients
+pub const DEFAULT_MAX_PAGE_SIZE: u64 = 1000;
+
 ///default page plugin
 pub trait PagePlugin: Send + Sync + Debug {
     ///the name
@@ -94,6 +97,13 @@ pub struct PageRequest {
     /// page page_size default 10
     pub page_size: u64,
     pub search_count: bool,
+    /// max page_size default 1000,not (de)serialized so clients can not change it
+    #[serde(skip, default = "default_max_page_size")]
+    max_page_size: u64,
+}
+
+fn default_max_page_size() -> u64 {
+    DEFAULT_MAX_PAGE_SIZE
 }
 
 impl PageRequest {
@@ -101,8 +111,17 @@ impl PageRequest {
         return PageRequest::new_total(page_no, page_size, DEFAULT_PAGE_SIZE);
     }
 
+    /// new PageRequest from client params, page_size will be clamp to DEFAULT_MAX_PAGE_SIZE
     pub fn new_option(page_no: &Option<u64>, page_size: &Option<u64>) -> Self {
-        return PageRequest::new(page_no.unwrap_or(1), page_size.unwrap_or(DEFAULT_PAGE_SIZE));
+        return PageRequest::new_option_max(page_no, page_size, DEFAULT_MAX_PAGE_SIZE);
+    }
+
+    /// new PageRequest from client params, page_size will be clamp to max_page_size
+    /// for example:
+    ///    PageRequest::new_option_max(&Some(1), &Some(1000000000), 500) // page_size = 500
+    pub fn new_option_max(page_no: &Option<u64>, page_size: &Option<u64>, max_page_size: u64) -> Self {
+        return PageRequest::new(page_no.unwrap_or(1), page_size.unwrap_or(DEFAULT_PAGE_SIZE))
+            .set_max_page_size(max_page_size);
     }
 
     pub fn new_total(page_no: u64, page_size: u64, total: u64) -> Self {
@@ -119,8 +138,23 @@ impl PageRequest {
             page_size,
             page_no: page_no,
             search_count: true,
+            max_page_size: DEFAULT_MAX_PAGE_SIZE,
         };
     }
+
+    /// set max page_size, and clamp the current page_size to it
+    pub fn set_max_page_size(mut self, max_page_size: u64) -> Self {
+        self.max_page_size = max_page_size;
+        if self.page_size > max_page_size {
+            self.page_size = max_page_size;
+        }
+        self
+    }
+
+    /// the max page_size,see set_max_page_size()
+    pub fn max_page_size(&self) -> u64 {
+        self.max_page_size
+    }
 }
 
 impl Default for PageRequest {
@@ -130,6 +164,7 @@ impl Default for PageRequest {
             page_size: DEFAULT_PAGE_SIZE,
             page_no: 1,
             search_count: true,
+            max_page_size: DEFAULT_MAX_PAGE_SIZE,
         };
     }
 }
@@ -208,6 +243,74 @@ impl<T> Page<T> {
             search_count: true,
         };
     }
+
+    /// sum pages, if the pages field is not set
+    fn sum_pages(&self) -> u64 {
+        if self.pages != 0 || self.page_size == 0 {
+            return self.pages;
+        }
+        let mut pages = self.total / self.page_size;
+        if self.total % self.page_size != 0 {
+            pages = pages + 1;
+        }
+        return pages;
+    }
+
+    /// is have next page
+    pub fn has_next(&self) -> bool {
+        self.page_no < self.sum_pages()
+    }
+
+    /// is have prev page
+    pub fn has_prev(&self) -> bool {
+        self.page_no > 1
+    }
+
+    /// the index(start with 0) of the first record on this page in all records
+    /// for example:
+    ///    Page::new(2, 10).first_item_index() // 10
+    pub fn first_item_index(&self) -> u64 {
+        if self.page_no > 0 {
+            (self.page_no - 1).saturating_mul(self.page_size)
+        } else {
+            0
+        }
+    }
+
+    /// map Page<T> to Page<R>, keep the page info
+    /// for example:
+    ///    let page: Page<BizActivityDTO> = page.map(|x| BizActivityDTO::from(x));
+    pub fn map<R, F>(self, f: F) -> Page<R>
+        where
+            F: FnMut(T) -> R,
+    {
+        Page {
+            records: self.records.into_iter().map(f).collect(),
+            total: self.total,
+            pages: self.pages,
+            page_no: self.page_no,
+            page_size: self.page_size,
+            search_count: self.search_count,
+        }
+    }
+
+    /// try map Page<T> to Page<R>, return the first error
+    /// for example:
+    ///    let page: Page<BizActivityDTO> = page.try_map(|x| BizActivityDTO::try_from(x))?;
+    pub fn try_map<R, E, F>(self, f: F) -> Result<Page<R>, E>
+        where
+            F: FnMut(T) -> Result<R, E>,
+    {
+        let records = self.records.into_iter().map(f).collect::<Result<Vec<R>, E>>()?;
+        Ok(Page {
+            records: records,
+            total: self.total,
+            pages: self.pages,
+            page_no: self.page_no,
+            page_size: self.page_size,
+            search_count: self.search_count,
+        })
+    }
 }
 
 impl<T> Default for Page<T> {
@@ -0,0 +1,260 @@
+use std::collections::HashSet;
+
+use serde_json::Value;
+
+use crate::core::db::DriverType;
+use crate::crud::CRUDTable;
+use crate::sql::lexer::{tokenize, Token};
+
+/// the redaction policy of sql log args,set on the log plugin
+/// for example:
+///     let policy = RedactPolicy::default()
+///         .table::<User>()
+///         .column("id_card")
+///         .pattern("*password*")
+///         .position(0)
+///         .max_len(64)
+///         .max_blob_len(64);
+///     rb.set_log_plugin(RbatisLogPlugin::default().redact(policy));
+#[derive(Clone, Debug)]
+pub struct RedactPolicy {
+    /// sensitive column names(lower case)
+    pub columns: HashSet<String>,
+    /// sensitive column name patterns(lower case),'*' match any chars,for example: "*password*","*_token"
+    pub patterns: Vec<String>,
+    /// mask the args at these positions(0 based) of every sql
+    pub positions: HashSet<usize>,
+    /// max chars of string arg,the longer will be truncated,0 is no limit
+    pub max_len: usize,
+    /// max bytes of blob(array of u8) arg,the longer will be replaced by "<blob n bytes>",0 is no limit(default).
+    /// an int array(for example the '= any($1)' arg of pg) is same as blob,so only enable it if the tables have binary columns
+    pub max_blob_len: usize,
+    /// the masked value
+    pub mask: String,
+}
+
+impl Default for RedactPolicy {
+    fn default() -> Self {
+        Self {
+            columns: HashSet::new(),
+            patterns: vec![],
+            positions: HashSet::new(),
+            max_len: 0,
+            max_blob_len: 0,
+            mask: "******".to_string(),
+        }
+    }
+}
+
+impl RedactPolicy {
+    /// add the sensitive columns of table,the fields mark by #[crud_table(sensitive)]
+    pub fn table<T>(mut self) -> Self where T: CRUDTable {
+        for column in T::sensitive_columns() {
+            self.columns.insert(column.to_lowercase());
+        }
+        self
+    }
+
+    pub fn column(mut self, column: &str) -> Self {
+        self.columns.insert(column.to_lowercase());
+        self
+    }
+
+    pub fn pattern(mut self, pattern: &str) -> Self {
+        self.patterns.push(pattern.to_lowercase());
+        self
+    }
+
+    pub fn position(mut self, index: usize) -> Self {
+        self.positions.insert(index);
+        self
+    }
+
+    pub fn max_len(mut self, max_len: usize) -> Self {
+        self.max_len = max_len;
+        self
+    }
+
+    pub fn max_blob_len(mut self, max_blob_len: usize) -> Self {
+        self.max_blob_len = max_blob_len;
+        self
+    }
+
+    /// is the column(or table.column) sensitive
+    pub fn is_sensitive(&self, column: &str) -> bool {
+        let column = column.rsplit('.').next().unwrap_or_default().to_lowercase();
+        self.columns.contains(&column) || self.patterns.iter().any(|x| match_pattern(x, &column))
+    }
+
+    /// mask the sensitive args and truncate the long args of sql
+    pub fn redact_args(&self, driver_type: &DriverType, sql: &str, args: &[Value]) -> Vec<Value> {
+        let columns = if self.columns.is_empty() && self.patterns.is_empty() {
+            vec![]
+        } else {
+            arg_columns(driver_type, sql)
+        };
+        let mut result = Vec::with_capacity(args.len());
+        for (index, arg) in args.iter().enumerate() {
+            let sensitive = self.positions.contains(&index)
+                || match columns.get(index) {
+                Some(Some(column)) => self.is_sensitive(column),
+                _ => false,
+            };
+            if sensitive {
+                result.push(Value::String(self.mask.clone()));
+            } else {
+                result.push(self.truncate(arg));
+            }
+        }
+        result
+    }
+
+    /// truncate the long string or blob
+    pub fn truncate(&self, arg: &Value) -> Value {
+        match arg {
+            Value::String(s) => {
+                if self.max_len != 0 && s.chars().count() > self.max_len {
+                    let head: String = s.chars().take(self.max_len).collect();
+                    return Value::String(format!("{}...({} chars)", head, s.chars().count()));
+                }
+            }
+            Value::Array(arr) => {
+                if self.max_blob_len != 0 && arr.len() > self.max_blob_len && arr.iter().all(|x| x.as_u64().map(|x| x <= 255).unwrap_or(false)) {
+                    return Value::String(format!("<blob {} bytes>", arr.len()));
+                }
+            }
+            _ => {}
+        }
+        arg.clone()
+    }
+}
+
+/// match the '*' pattern
+fn match_pattern(pattern: &str, arg: &str) -> bool {
+    let parts: Vec<&str> = pattern.split('*').collect();
+    if parts.len() == 1 {
+        return pattern == arg;
+    }
+    let mut rest = arg;
+    for (index, part) in parts.iter().enumerate() {
+        if index == 0 {
+            if !rest.starts_with(part) {
+                return false;
+            }
+            rest = &rest[part.len()..];
+        } else if index == parts.len() - 1 {
+            return rest.ends_with(part);
+        } else {
+            match rest.find(part) {
+                Some(i) => rest = &rest[i + part.len()..],
+                None => return false,
+            }
+        }
+    }
+    true
+}
+
+/// the column of every arg,for example:
+///   "update a set name = ?,pwd = ? where id in (?,?)" -> [Some("name"),Some("pwd"),Some("id"),Some("id")]
+///   "insert into a (id,pwd) values (?,?)" -> [Some("id"),Some("pwd")]
+pub fn arg_columns(driver_type: &DriverType, sql: &str) -> Vec<Option<String>> {
+    let tokens = tokenize(driver_type, sql);
+    let mut result: Vec<Option<String>> = vec![];
+    let mut put = |index: usize, column: Option<String>| {
+        if result.len() <= index {
+            result.resize(index + 1, None);
+        }
+        result[index] = column;
+    };
+    let mut next_index = 0;
+    //the last column name
+    let mut candidate: Option<String> = None;
+    //the column of compare
+    let mut current: Option<String> = None;
+    let mut between = false;
+    //insert into a (columns) values (..)
+    let mut insert = false;
+    let mut insert_columns: Option<Vec<String>> = None;
+    let mut in_values = false;
+    let mut depth = 0;
+    let mut values_item = 0;
+    for (index, token) in tokens.iter().enumerate() {
+        match token {
+            Token::Ident(w) => {
+                candidate = Some(w.clone());
+            }
+            Token::Word(w) => {
+                match w.as_str() {
+                    "insert" => {
+                        insert = true;
+                    }
+                    "values" => {
+                        in_values = insert;
+                        depth = 0;
+                    }
+                    "like" | "ilike" | "in" | "regexp" | "glob" | "is" => {
+                        current = candidate.clone();
+                    }
+                    "between" => {
+                        current = candidate.clone();
+                        between = true;
+                    }
+                    "and" if between => {
+                        between = false;
+                    }
+                    "and" | "or" | "where" | "set" | "on" | "having" | "when" | "then" | "else"
+                    | "limit" | "offset" | "order" | "group" | "select" | "from" | "top" | "fetch" => {
+                        current = None;
+                        candidate = None;
+                    }
+                    "not" | "escape" | "lower" | "upper" | "any" | "all" => {}
+                    _ => {
+                        //function name is not column
+                        if tokens.get(index + 1) != Some(&Token::Punct('(')) {
+                            candidate = Some(w.clone());
+                        }
+                    }
+                }
+            }
+            Token::Op(_) => {
+                current = candidate.clone();
+            }
+            Token::Punct('(') => {
+                depth += 1;
+                if in_values && depth == 1 {
+                    values_item = 0;
+                } else if insert && !in_values && insert_columns.is_none() {
+                    let mut columns = vec![];
+                    for x in &tokens[index + 1..] {
+                        match x {
+                            Token::Word(w) | Token::Ident(w) => columns.push(w.clone()),
+                            Token::Punct(')') => break,
+                            _ => {}
+                        }
+                    }
+                    insert_columns = Some(columns);
+                }
+            }
+            Token::Punct(')') => {
+                depth -= 1;
+            }
+            Token::Punct(',') => {
+                if in_values && depth == 1 {
+                    values_item += 1;
+                }
+            }
+            Token::Arg(arg_index) => {
+                let arg_index = arg_index.unwrap_or(next_index);
+                next_index = arg_index + 1;
+                if in_values && depth >= 1 {
+                    let column = insert_columns.as_ref().and_then(|x| x.get(values_item).cloned());
+                    put(arg_index, column);
+                } else {
+                    put(arg_index, current.clone());
+                }
+            }
+            _ => {}
+        }
+    }
+    result
+}
@@ -0,0 +1,143 @@
+use std::fmt::Debug;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde_json::Value;
+
+use crate::core::db::DriverType;
+use crate::plugin::intercept::SqlOperation;
+use crate::rbatis::Rbatis;
+
+/// slow sql plugin,RBatisConnExecutor/RBatisTxExecutor measure every exec/fetch
+/// and record the sql use time >= threshold()
+pub trait SlowQueryPlugin: Send + Sync + Debug {
+    ///the name
+    fn name(&self) -> &str {
+        std::any::type_name::<Self>()
+    }
+
+    /// sql use time >= threshold is slow
+    fn threshold(&self) -> Duration;
+
+    /// run 'explain' for slow select on the connection of the select,skip it in transaction
+    fn is_explain(&self) -> bool {
+        false
+    }
+
+    /// record an slow sql
+    fn record(&self, rb: &Rbatis, query: SlowQuery);
+
+    /// the slowest sql records,slowest first
+    fn slowest(&self) -> Vec<SlowQuery>;
+}
+
+/// an slow sql record
+#[derive(Clone, Debug)]
+pub struct SlowQuery {
+    pub sql: String,
+    pub args: Vec<Value>,
+    pub elapsed: Duration,
+    pub operation: SqlOperation,
+    pub table: Option<String>,
+    pub in_transaction: bool,
+    /// rows of 'explain',if is_explain() and the sql is select(not in transaction)
+    pub explain: Option<Value>,
+}
+
+/// log the slow sql with warn level,and keep the top_n slowest sql in memory
+/// for example:
+///     rb.set_slow_query_plugin(Some(RbatisSlowQueryPlugin::new(Duration::from_millis(200))));
+///     //...
+///     let slowest = rb.slow_queries();
+#[derive(Debug)]
+pub struct RbatisSlowQueryPlugin {
+    pub threshold: Duration,
+    pub explain: bool,
+    /// max records of slowest(),default 100
+    pub top_n: usize,
+    slowest: Mutex<Vec<SlowQuery>>,
+}
+
+impl RbatisSlowQueryPlugin {
+    pub fn new(threshold: Duration) -> Self {
+        Self {
+            threshold,
+            explain: false,
+            top_n: 100,
+            slowest: Mutex::new(vec![]),
+        }
+    }
+
+    /// clear the slowest records
+    pub fn clear(&self) {
+        if let Ok(mut slowest) = self.slowest.lock() {
+            slowest.clear();
+        }
+    }
+}
+
+impl SlowQueryPlugin for RbatisSlowQueryPlugin {
+    fn threshold(&self) -> Duration {
+        self.threshold
+    }
+
+    fn is_explain(&self) -> bool {
+        self.explain
+    }
+
+    fn record(&self, rb: &Rbatis, query: SlowQuery) {
+        if rb.log_plugin.is_enable() {
+            let args = match rb.log_plugin.redact_policy() {
+                Some(policy) => policy.redact_args(&rb.driver_type().unwrap_or(DriverType::None), &query.sql, &query.args),
+                None => query.args.clone(),
+            };
+            let mut data = format!(
+                "SlowSql({:?}) ==> {}\n{}[rbatis] [] Args   ==> {}",
+                query.elapsed,
+                &query.sql,
+                crate::utils::string_util::LOG_SPACE,
+                Value::Array(args).to_string()
+            );
+            if let Some(explain) = &query.explain {
+                data.push_str(&format!("\n{}[rbatis] [] Explain <== {}", crate::utils::string_util::LOG_SPACE, explain));
+            }
+            rb.log_plugin.warn(&data);
+        }
+        if self.top_n == 0 {
+            return;
+        }
+        if let Ok(mut slowest) = self.slowest.lock() {
+            if slowest.len() >= self.top_n {
+                //the last is the fastest
+                if slowest[slowest.len() - 1].elapsed >= query.elapsed {
+                    return;
+                }
+                slowest.pop();
+            }
+            let index = slowest.iter().position(|x| x.elapsed < query.elapsed).unwrap_or(slowest.len());
+            slowest.insert(index, query);
+        }
+    }
+
+    fn slowest(&self) -> Vec<SlowQuery> {
+        match self.slowest.lock() {
+            Ok(slowest) => slowest.clone(),
+            Err(_) => vec![],
+        }
+    }
+}
+
+/// the explain sql of driver,None if the sql is not select or the driver not support
+pub fn make_explain_sql(driver_type: &DriverType, sql: &str) -> Option<String> {
+    let trim_sql = sql.trim_start();
+    let head = trim_sql.get(0..6).unwrap_or_default().to_lowercase();
+    if !head.eq("select") && !head.starts_with("with") {
+        return None;
+    }
+    match driver_type {
+        DriverType::Mysql | DriverType::Postgres => Some(format!("explain {}", trim_sql)),
+        DriverType::Sqlite => Some(format!("explain query plan {}", trim_sql)),
+        //mssql showplan must be set in an separate batch
+        _ => None,
+    }
+}
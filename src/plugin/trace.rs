@@ -0,0 +1,135 @@
+use tracing::field::Empty;
+use tracing::Span;
+
+use crate::core::db::DriverType;
+use crate::core::Error;
+use crate::plugin::intercept::{InterceptContext, SqlOperation};
+use crate::plugin::page::Page;
+
+/// the 'db.system' of OpenTelemetry database semantic conventions
+pub fn db_system(driver_type: &DriverType) -> &'static str {
+    match driver_type {
+        DriverType::Mysql => "mysql",
+        DriverType::Postgres => "postgresql",
+        DriverType::Sqlite => "sqlite",
+        DriverType::Mssql => "mssql",
+        _ => "other_sql",
+    }
+}
+
+/// the 'db.operation' of sql,the first keyword,for example: "select * from a" -> "select"
+pub fn db_operation(sql: &str) -> String {
+    sql.trim_start()
+        .split(|c: char| c.is_whitespace() || c == '(')
+        .next()
+        .unwrap_or_default()
+        .to_lowercase()
+}
+
+macro_rules! sql_span {
+    ($name:expr, $driver_type:expr, $context:expr) => {
+        tracing::info_span!(
+            $name,
+            otel.name = Empty,
+            otel.kind = "client",
+            otel.status_code = Empty,
+            db.system = db_system($driver_type),
+            db.operation = Empty,
+            db.sql.table = $context.table.as_deref().unwrap_or_default(),
+            db.statement = Empty,
+            rows = Empty,
+            error = Empty,
+        )
+    };
+}
+
+/// make an span of exec/fetch,the span is child of parent(the tx span) if have,
+/// db.statement is recorded by record_statement() after the Intercept chain
+pub fn make_sql_span(parent: Option<&Span>, driver_type: &DriverType, context: &InterceptContext) -> Span {
+    let make = || match context.operation {
+        SqlOperation::Exec => sql_span!("rbatis.exec", driver_type, context),
+        SqlOperation::Fetch => sql_span!("rbatis.fetch", driver_type, context),
+        SqlOperation::PageCount => sql_span!("rbatis.page_count", driver_type, context),
+        SqlOperation::Aggregate => sql_span!("rbatis.aggregate", driver_type, context),
+    };
+    match parent {
+        Some(parent) => parent.in_scope(make),
+        None => make(),
+    }
+}
+
+/// make an span of fetch_page,the count sql and the page sql are children of it
+pub fn make_page_span(parent: Option<&Span>, driver_type: &DriverType) -> Span {
+    let make = || tracing::info_span!(
+        "rbatis.fetch_page",
+        otel.kind = "client",
+        otel.status_code = Empty,
+        db.system = db_system(driver_type),
+        rows = Empty,
+        total = Empty,
+        error = Empty,
+    );
+    match parent {
+        Some(parent) => parent.in_scope(make),
+        None => make(),
+    }
+}
+
+/// make an span of transaction,from begin to commit/rollback
+pub fn make_tx_span(driver_type: &DriverType) -> Span {
+    tracing::info_span!(
+        "rbatis.tx",
+        otel.kind = "client",
+        otel.status_code = Empty,
+        db.system = db_system(driver_type),
+        tx.end = Empty,
+        error = Empty,
+    )
+}
+
+/// record the sql(after the Intercept chain) into span
+pub fn record_statement(span: &Span, context: &InterceptContext, sql: &str) {
+    let operation = db_operation(sql);
+    match &context.table {
+        Some(table) => span.record("otel.name", &format!("{} {}", operation, table).as_str()),
+        None => span.record("otel.name", &operation.as_str()),
+    };
+    span.record("db.operation", &operation.as_str());
+    span.record("db.statement", &sql);
+}
+
+/// record rows(returned or affected) or the error into span
+pub fn record_result<T>(span: &Span, result: &Result<T, Error>, rows: impl Fn(&T) -> u64) {
+    match result {
+        Ok(v) => {
+            span.record("rows", &rows(v));
+        }
+        Err(e) => {
+            span.record("otel.status_code", &"ERROR");
+            span.record("error", &e.to_string().as_str());
+        }
+    }
+}
+
+/// record rows and total of fetch_page into span
+pub fn record_page<T>(span: &Span, result: &Result<Page<T>, Error>) {
+    match result {
+        Ok(page) => {
+            span.record("rows", &(page.records.len() as u64));
+            span.record("total", &page.total);
+        }
+        Err(e) => {
+            span.record("otel.status_code", &"ERROR");
+            span.record("error", &e.to_string().as_str());
+        }
+    }
+}
+
+/// record the end of tx,end is "commit" or "rollback"
+pub fn record_tx_end(span: &Span, end: &str, result: &Result<(), Error>) {
+    span.record("tx.end", &end);
+    if let Err(e) = result {
+        span.record("otel.status_code", &"ERROR");
+        span.record("error", &e.to_string().as_str());
+    }
+}
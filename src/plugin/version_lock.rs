@@ -1,6 +1,10 @@
 use serde_json::Number;
 use std::fmt::Debug;
+use async_trait::async_trait;
+
 use crate::crud::{CRUDTable, Skip};
+use crate::executor::ExecutorMut;
+use crate::wrapper::Wrapper;
 use serde::{Serialize, Deserialize, Serializer, Deserializer};
 use crate::DriverType;
 use std::collections::HashMap;
@@ -98,12 +102,13 @@ impl<'de, T> Deserialize<'de> for TableNoVersion<T> where T: CRUDTable {
 }
 
 
+#[async_trait]
 impl<T> CRUDTable for TableNoVersion<T> where T: CRUDTable {
     fn is_use_plugin(plugin_name: &str) -> bool {
         if plugin_name.eq(std::any::type_name::<RbatisVersionLockPlugin>()) {
             return false;
         }
-        return true;
+        return T::is_use_plugin(plugin_name);
     }
 
     fn table_name() -> String {
@@ -118,6 +123,10 @@ impl<T> CRUDTable for TableNoVersion<T> where T: CRUDTable {
         T::formats(driver_type)
     }
 
+    fn sensitive_columns() -> Vec<String> {
+        T::sensitive_columns()
+    }
+
     fn make_value_sql_arg(
         &self,
         db_type: &DriverType,
@@ -126,6 +135,48 @@ impl<T> CRUDTable for TableNoVersion<T> where T: CRUDTable {
     ) -> crate::Result<(String, String, Vec<serde_json::Value>)> {
         T::make_value_sql_arg(&self.table, db_type, index,skips)
     }
+
+    async fn before_insert<E>(&self, executor: &mut E) -> crate::Result<Option<Self>>
+        where
+            E: ExecutorMut + Send + ?Sized,
+    {
+        Ok(self.table.before_insert(executor).await?.map(|table| TableNoVersion { table }))
+    }
+
+    async fn after_insert<E>(&self, executor: &mut E) -> crate::Result<()>
+        where
+            E: ExecutorMut + Send + ?Sized,
+    {
+        self.table.after_insert(executor).await
+    }
+
+    async fn before_update<E>(&mut self, executor: &mut E) -> crate::Result<()>
+        where
+            E: ExecutorMut + Send + ?Sized,
+    {
+        self.table.before_update(executor).await
+    }
+
+    async fn after_update<E>(&mut self, executor: &mut E) -> crate::Result<()>
+        where
+            E: ExecutorMut + Send + ?Sized,
+    {
+        self.table.after_update(executor).await
+    }
+
+    async fn before_delete<E>(executor: &mut E, w: &Wrapper) -> crate::Result<()>
+        where
+            E: ExecutorMut + Send + ?Sized,
+    {
+        T::before_delete(executor, w).await
+    }
+
+    async fn after_fetch<E>(&mut self, executor: &mut E) -> crate::Result<()>
+        where
+            E: ExecutorMut + Send + ?Sized,
+    {
+        self.table.after_fetch(executor).await
+    }
 }
 
 impl<T> Deref for TableNoVersion<T> where T: CRUDTable {
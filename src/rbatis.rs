@@ -2,7 +2,7 @@ use std::borrow::BorrowMut;
 use std::cell::Cell;
 use std::collections::HashMap;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use once_cell::sync::OnceCell;
 use rbatis_core::db::DBConnectOption;
 use serde::de::DeserializeOwned;
@@ -14,10 +14,13 @@ use crate::core::db::{DBExecResult, DBPool, DBPoolConn, DBPoolOptions, DBQuery,
 use crate::core::Error;
 use crate::crud::CRUDTable;
 use crate::executor::{RBatisConnExecutor, RBatisTxExecutor, RbatisExecutor};
-use crate::plugin::intercept::SqlIntercept;
+use crate::plugin::audit::AuditPlugin;
+use crate::plugin::intercept::{Intercept, SqlIntercept};
 use crate::plugin::log::{LogPlugin, RbatisLogPlugin};
 use crate::plugin::logic_delete::{LogicDelete, RbatisLogicDeletePlugin};
 use crate::plugin::page::{IPage, IPageRequest, Page, PagePlugin, RbatisPagePlugin};
+use crate::plugin::metrics::{MetricsPlugin, MetricsSnapshot};
+use crate::plugin::slow_query::{SlowQuery, SlowQueryPlugin};
 use crate::plugin::version_lock::{RbatisVersionLockPlugin, VersionLockPlugin};
 use crate::sql::PageLimit;
 use crate::utils::error_util::ToResult;
@@ -34,12 +37,20 @@ pub struct Rbatis {
     pub page_plugin: Box<dyn PagePlugin>,
     // sql intercept vec chain
     pub sql_intercepts: Vec<Box<dyn SqlIntercept>>,
+    // async intercept vec chain,run after sql_intercepts
+    pub(crate) intercepts: Vec<Box<dyn Intercept>>,
     // logic delete plugin
     pub logic_plugin: Option<Box<dyn LogicDelete>>,
     // log plugin
     pub log_plugin: Arc<Box<dyn LogPlugin>>,
     // version lock plugin
     pub version_lock_plugin: Option<Box<dyn VersionLockPlugin>>,
+    // slow sql plugin
+    pub(crate) slow_query_plugin: Option<Box<dyn SlowQueryPlugin>>,
+    // sql metrics plugin
+    pub(crate) metrics_plugin: Option<Box<dyn MetricsPlugin>>,
+    // data change audit plugin
+    pub(crate) audit_plugin: Option<Box<dyn AuditPlugin>>,
     // sql param binder
     pub encoder: fn(q: &mut DBQuery, arg: &serde_json::Value) -> crate::Result<()>,
 }
@@ -50,8 +61,12 @@ impl Debug for Rbatis {
             .field("pool",&self.pool)
             .field("page_plugin",&self.page_plugin)
             .field("sql_intercepts",&self.sql_intercepts)
+            .field("intercepts",&self.intercepts)
             .field("logic_plugin",&self.logic_plugin)
             .field("version_lock_plugin",&self.version_lock_plugin)
+            .field("slow_query_plugin",&self.slow_query_plugin)
+            .field("metrics_plugin",&self.metrics_plugin)
+            .field("audit_plugin",&self.audit_plugin)
             .finish()
     }
 }
@@ -102,9 +117,13 @@ impl Rbatis {
             pool: OnceCell::new(),
             page_plugin: option.page_plugin,
             sql_intercepts: option.sql_intercepts,
+            intercepts: vec![],
             logic_plugin: option.logic_plugin,
             log_plugin: option.log_plugin,
             version_lock_plugin: None,
+            slow_query_plugin: None,
+            metrics_plugin: None,
+            audit_plugin: None,
             encoder: |q,arg|{
                 q.bind_value(arg)?;
                 Ok(())
@@ -186,6 +205,64 @@ impl Rbatis {
         }
     }
 
+    pub fn set_slow_query_plugin(&mut self, arg: Option<impl SlowQueryPlugin + 'static>) {
+        match arg {
+            Some(v) => {
+                self.slow_query_plugin = Some(Box::new(v));
+            }
+            None => {
+                self.slow_query_plugin = None;
+            }
+        }
+    }
+
+    pub fn slow_query_plugin(&self) -> Option<&dyn SlowQueryPlugin> {
+        self.slow_query_plugin.as_deref()
+    }
+
+    /// the slowest sql records of slow_query_plugin,slowest first
+    pub fn slow_queries(&self) -> Vec<SlowQuery> {
+        match &self.slow_query_plugin {
+            Some(plugin) => plugin.slowest(),
+            None => vec![],
+        }
+    }
+
+    pub fn set_metrics_plugin(&mut self, arg: Option<impl MetricsPlugin + 'static>) {
+        match arg {
+            Some(v) => {
+                self.metrics_plugin = Some(Box::new(v));
+            }
+            None => {
+                self.metrics_plugin = None;
+            }
+        }
+    }
+
+    pub fn metrics_plugin(&self) -> Option<&dyn MetricsPlugin> {
+        self.metrics_plugin.as_deref()
+    }
+
+    /// snapshot of metrics_plugin,None if not set
+    pub fn metrics(&self) -> Option<MetricsSnapshot> {
+        self.metrics_plugin.as_ref().map(|x| x.snapshot())
+    }
+
+    pub fn set_audit_plugin(&mut self, arg: Option<impl AuditPlugin + 'static>) {
+        match arg {
+            Some(v) => {
+                self.audit_plugin = Some(Box::new(v));
+            }
+            None => {
+                self.audit_plugin = None;
+            }
+        }
+    }
+
+    pub fn audit_plugin(&self) -> Option<&dyn AuditPlugin> {
+        self.audit_plugin.as_deref()
+    }
+
     pub fn set_page_plugin(&mut self, arg: impl PagePlugin + 'static) {
         self.page_plugin = Box::new(arg);
     }
@@ -198,6 +275,18 @@ impl Rbatis {
         self.sql_intercepts = arg;
     }
 
+    pub fn add_intercept(&mut self, arg: impl Intercept + 'static) {
+        self.intercepts.push(Box::new(arg));
+    }
+
+    pub fn set_intercepts(&mut self, arg: Vec<Box<dyn Intercept>>) {
+        self.intercepts = arg;
+    }
+
+    pub fn intercepts(&self) -> &[Box<dyn Intercept>] {
+        &self.intercepts
+    }
+
     /// get conn pool
     pub fn get_pool(&self) -> Result<&DBPool, Error> {
         let p = self.pool.get();
@@ -216,7 +305,12 @@ impl Rbatis {
     /// get an DataBase Connection used for the next step
     pub async fn acquire(&self) -> Result<RBatisConnExecutor<'_>, Error> {
         let pool = self.get_pool()?;
-        let conn = pool.acquire().await?;
+        let start = Instant::now();
+        let conn = pool.acquire().await;
+        if let Some(plugin) = &self.metrics_plugin {
+            plugin.record_acquire(start.elapsed(), conn.is_err());
+        }
+        let conn = conn?;
         return Ok(RBatisConnExecutor {
             conn: conn,
             rb: &self,
@@ -226,8 +320,16 @@ impl Rbatis {
     /// get an DataBase Connection,and call begin method,used for the next step
     pub async fn acquire_begin(&self) -> Result<RBatisTxExecutor<'_>, Error> {
         let pool = self.get_pool()?;
-        let conn = pool.begin().await?;
+        let start = Instant::now();
+        let conn = pool.begin().await;
+        if let Some(plugin) = &self.metrics_plugin {
+            plugin.record_acquire(start.elapsed(), conn.is_err());
+        }
+        let conn = conn?;
         return Ok(RBatisTxExecutor {
+            #[cfg(feature = "tracing")]
+            span: crate::plugin::trace::make_tx_span(&conn.driver_type),
+            tx_id: crate::executor::new_tx_id(),
             conn: conn,
             rb: &self,
         });
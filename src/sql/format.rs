@@ -0,0 +1,86 @@
+use serde_json::Value;
+
+use crate::core::db::DriverType;
+use crate::sql::lexer::{tokenize_spans, Token};
+
+/// replace the placeholders of sql with the sql literal of args,the result can be executed by database.
+/// placeholders in string literals,quoted identifiers and comments are skipped,
+/// Mysql/Sqlite use '?',Postgres use '$1',Mssql use '@p1'.
+/// for example:
+///     format_sql(&DriverType::Postgres, "select * from a where name = $1 and remark = '$1?'", &vec![json!("it's")])
+///     -> "select * from a where name = 'it''s' and remark = '$1?'"
+pub fn format_sql(driver_type: &DriverType, sql: &str, args: &[Value]) -> String {
+    let chars: Vec<char> = sql.chars().collect();
+    let mut result = String::with_capacity(sql.len());
+    let mut next_index = 0;
+    let mut copied = 0;
+    let tokens = tokenize_spans(driver_type, sql);
+    for (i, (token, span)) in tokens.iter().enumerate() {
+        if let Token::Arg(index) = token {
+            let index = index.unwrap_or(next_index);
+            next_index = index + 1;
+            if let Some(arg) = args.get(index) {
+                result.extend(&chars[copied..span.start]);
+                //the pg array arg of '= any($1)' is not bytea
+                let is_any_arg = i >= 2
+                    && tokens[i - 1].0 == Token::Punct('(')
+                    && (tokens[i - 2].0.is_word("any") || tokens[i - 2].0.is_word("all"));
+                match arg {
+                    Value::Array(arr) if is_any_arg && *driver_type == DriverType::Postgres => {
+                        result.push_str(&pg_array_literal(driver_type, arr));
+                    }
+                    _ => result.push_str(&sql_literal(driver_type, arg)),
+                }
+                copied = span.end;
+            }
+        }
+    }
+    result.extend(&chars[copied..]);
+    result
+}
+
+/// the sql literal of an arg,for example: "it's" -> 'it''s',null -> NULL,Vec<u8> -> X'01FF'(pg: '\x01ff')
+pub fn sql_literal(driver_type: &DriverType, arg: &Value) -> String {
+    match arg {
+        Value::Null => "NULL".to_string(),
+        Value::Bool(b) => match driver_type {
+            DriverType::Mysql | DriverType::Postgres => b.to_string(),
+            _ => (if *b { "1" } else { "0" }).to_string(),
+        },
+        Value::Number(n) => n.to_string(),
+        Value::String(s) => quote_string(driver_type, s),
+        Value::Array(arr) => {
+            //Vec<u8> is blob
+            if !arr.is_empty() && arr.iter().all(|x| x.as_u64().map(|x| x <= 255).unwrap_or(false)) {
+                let hex: String = arr.iter().map(|x| format!("{:02x}", x.as_u64().unwrap_or(0))).collect();
+                return match driver_type {
+                    DriverType::Postgres => format!("'\\x{}'", hex),
+                    DriverType::Mssql => format!("0x{}", hex.to_uppercase()),
+                    _ => format!("X'{}'", hex.to_uppercase()),
+                };
+            }
+            if *driver_type == DriverType::Postgres {
+                return pg_array_literal(driver_type, arr);
+            }
+            quote_string(driver_type, &arg.to_string())
+        }
+        Value::Object(_) => quote_string(driver_type, &arg.to_string()),
+    }
+}
+
+fn pg_array_literal(driver_type: &DriverType, arr: &[Value]) -> String {
+    let items: Vec<String> = arr.iter().map(|x| sql_literal(driver_type, x)).collect();
+    format!("ARRAY[{}]", items.join(","))
+}
+
+fn quote_string(driver_type: &DriverType, arg: &str) -> String {
+    let mut escaped = arg.replace('\'', "''");
+    match driver_type {
+        DriverType::Mysql => {
+            escaped = escaped.replace('\\', "\\\\");
+            format!("'{}'", escaped)
+        }
+        DriverType::Mssql => format!("N'{}'", escaped),
+        _ => format!("'{}'", escaped),
+    }
+}
@@ -0,0 +1,147 @@
+use std::ops::Range;
+
+use crate::core::db::DriverType;
+
+/// an sql token
+#[derive(Clone, Debug, PartialEq)]
+pub enum Token {
+    /// keyword or identifier(lower case)
+    Word(String),
+    /// quoted identifier,for example: "a",`a`,[a](mssql)
+    Ident(String),
+    /// string literal(unescaped)
+    Str(String),
+    Number(String),
+    /// arg placeholder,the index of $1/@p1,None is '?'
+    Arg(Option<usize>),
+    /// compare operator,for example: =,<>,>=
+    Op(String),
+    Punct(char),
+}
+
+impl Token {
+    /// the name of Word/Ident
+    pub fn name(&self) -> Option<&str> {
+        match self {
+            Token::Word(w) | Token::Ident(w) => Some(w),
+            _ => None,
+        }
+    }
+
+    pub fn is_word(&self, word: &str) -> bool {
+        match self {
+            Token::Word(w) => w == word,
+            _ => false,
+        }
+    }
+}
+
+/// split sql into tokens,comments are skipped
+pub fn tokenize(driver_type: &DriverType, sql: &str) -> Vec<Token> {
+    tokenize_spans(driver_type, sql).into_iter().map(|(token, _)| token).collect()
+}
+
+/// split sql into tokens with the char range of each token in sql,comments are skipped
+pub fn tokenize_spans(driver_type: &DriverType, sql: &str) -> Vec<(Token, Range<usize>)> {
+    let chars: Vec<char> = sql.chars().collect();
+    let mut tokens = vec![];
+    let mut i = 0;
+    let read_number = |i: &mut usize| -> Option<usize> {
+        let start = *i;
+        while *i < chars.len() && chars[*i].is_ascii_digit() {
+            *i += 1;
+        }
+        chars[start..*i].iter().collect::<String>().parse::<usize>().ok()
+    };
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        let start = i;
+        let token = if c.is_whitespace() {
+            i += 1;
+            None
+        } else if c == '-' && next == Some('-') {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+            None
+        } else if c == '/' && next == Some('*') {
+            i += 2;
+            while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                i += 1;
+            }
+            i = (i + 2).min(chars.len());
+            None
+        } else if c == '$' && next == Some('$') && *driver_type == DriverType::Postgres {
+            //dollar quoted string
+            i += 2;
+            let mut value = String::new();
+            while i < chars.len() && !(chars[i] == '$' && chars.get(i + 1) == Some(&'$')) {
+                value.push(chars[i]);
+                i += 1;
+            }
+            i = (i + 2).min(chars.len());
+            Some(Token::Str(value))
+        } else if c == '\'' || c == '"' || c == '`' || (c == '[' && *driver_type == DriverType::Mssql) {
+            let end = if c == '[' { ']' } else { c };
+            let mut value = String::new();
+            i += 1;
+            while i < chars.len() {
+                if chars[i] == '\\' && c == '\'' && *driver_type == DriverType::Mysql {
+                    if let Some(x) = chars.get(i + 1) {
+                        value.push(*x);
+                    }
+                    i += 2;
+                    continue;
+                }
+                if chars[i] == end {
+                    if chars.get(i + 1) == Some(&end) {
+                        value.push(end);
+                        i += 2;
+                        continue;
+                    }
+                    break;
+                }
+                value.push(chars[i]);
+                i += 1;
+            }
+            i = (i + 1).min(chars.len());
+            if c == '\'' {
+                Some(Token::Str(value))
+            } else {
+                Some(Token::Ident(value.to_lowercase()))
+            }
+        } else if c == '?' && *driver_type != DriverType::Postgres && *driver_type != DriverType::Mssql {
+            i += 1;
+            Some(Token::Arg(None))
+        } else if c == '$' && next.map(|x| x.is_ascii_digit()).unwrap_or(false) && *driver_type == DriverType::Postgres {
+            i += 1;
+            Some(Token::Arg(read_number(&mut i).map(|x| x.saturating_sub(1))))
+        } else if c == '@' && next == Some('p') && chars.get(i + 2).map(|x| x.is_ascii_digit()).unwrap_or(false) && *driver_type == DriverType::Mssql {
+            i += 2;
+            Some(Token::Arg(read_number(&mut i).map(|x| x.saturating_sub(1))))
+        } else if c.is_ascii_digit() {
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+            Some(Token::Number(chars[start..i].iter().collect()))
+        } else if c.is_alphanumeric() || c == '_' {
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '$') {
+                i += 1;
+            }
+            Some(Token::Word(chars[start..i].iter().collect::<String>().to_lowercase()))
+        } else if c == '=' || c == '<' || c == '>' || c == '!' {
+            while i < chars.len() && (chars[i] == '=' || chars[i] == '<' || chars[i] == '>' || chars[i] == '!') {
+                i += 1;
+            }
+            Some(Token::Op(chars[start..i].iter().collect()))
+        } else {
+            i += 1;
+            Some(Token::Punct(c))
+        };
+        if let Some(token) = token {
+            tokens.push((token, start..i));
+        }
+    }
+    tokens
+}
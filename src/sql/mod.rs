@@ -1,8 +1,11 @@
 use serde_json::Value;
 
+pub mod format;
+pub mod lexer;
 pub mod limit;
 pub mod rule;
 pub mod template;
 
+pub use format::{format_sql, sql_literal};
 pub use limit::PageLimit;
 pub use template::TEMPLATE;
@@ -80,6 +80,10 @@ gen_template!({
             delete_from: "delete from",
             from: "from",
             r#as: "as",
+            inner_join: "inner join",
+            left_join: "left join",
+            on: "on",
+            exists: "exists",
             offset: "offset",
             rows_fetch_next: "rows fetch next",
             rows_only: "rows only",
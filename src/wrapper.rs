@@ -1,17 +1,33 @@
 use std::collections::HashMap;
 use std::fmt::{Debug, Formatter};
-use std::ops::Add;
+use std::ops::{Add, Deref};
+
+use once_cell::sync::OnceCell;
 
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Map, Value};
 
+use crate::column::{ColumnArg, IntoColumn};
 use crate::core::convert::StmtConvert;
 use crate::core::db::DriverType;
 use crate::core::Error;
+use crate::crud::CRUDTable;
+use crate::sql::PageLimit;
+use crate::sql::rule::SqlRule;
 
 /// The packing/Wrapper of the SQL
 /// SQL passed into the Wrapper keep the keyword uppercase
 ///
+/// The Wrapper only save a condition tree(conditions,group by,having,order by,limit),
+/// sql and args are rendered by build(driver_type) at execution time,
+/// so the placeholders($1,$2... or @p1,@p2...) are always numbered right.
+///
+/// migration(breaking change): the fields of Wrapper are private,read them by the getters(conditions(),joins()...).
+/// the old fields w.sql and w.args are still readable(rendered for w.driver_type),
+/// but they are deprecated and read only,assign or push to them not compile any more,
+/// use set_sql()/push_sql()/set_args()/push_arg()/set_format() to change the Wrapper,
+/// and w.build(&driver_type) or w.sql()/w.args() to render.
+///
 /// for Example:
 ///         let w = Wrapper::new(&DriverType::Mysql)
 ///             .push_sql(“id == 1”)
@@ -33,120 +49,997 @@ use crate::core::Error;
 ///             .group_by(&["id"])
 ///             .order_by(true, &["id", "name"])
 ///             ;
+///         let (sql, args) = w.build(&DriverType::Mysql);
 ///
 #[derive(Clone)]
 pub struct Wrapper {
-    pub driver_type: DriverType,
+    driver_type: DriverType,
+    formats: HashMap<String, fn(arg: &str) -> String>,
+    /// select of the sub query,see select()
+    select: Option<Select>,
+    /// alias of the main table
+    table_alias: Option<String>,
+    joins: Vec<Join>,
+    /// where conditions
+    conditions: Vec<Node>,
+    group_by_columns: Vec<String>,
+    having_conditions: Vec<Node>,
+    order_by_columns: Vec<OrderBy>,
+    limit_size: Option<u64>,
+    /// sql/args pushed after group by,having,order by or limit
+    tail: Vec<Node>,
+    /// (from,to) replace of the rendered sql,see trim_value()
+    replaces: Vec<(String, String)>,
+    /// the cache of w.sql/w.args,cleared when self changed
+    rendered: OnceCell<Rendered>,
+}
+
+/// the sql and args rendered for Wrapper.driver_type,they keep the old fields w.sql and w.args readable(by Deref).
+/// deprecated: w.sql/w.args are read only,use Wrapper::build(driver_type) to render for the driver of connection
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Rendered {
     pub sql: String,
     pub args: Vec<Value>,
+}
+
+/// node of the Wrapper condition tree
+#[derive(Clone, Debug)]
+pub enum Node {
+    /// link next condition with 'and'
+    And,
+    /// link next condition with 'or'
+    Or,
+    /// column op arg,for example: "id = ?"
+    Compare { column: String, op: CompareOp, arg: Value },
+    /// column (not) between min and max
+    Between { column: String, not: bool, min: Value, max: Value },
+    /// column (not) in (args)
+    In { column: String, not: bool, args: Vec<Value> },
+    /// column is (not) NULL
+    IsNull { column: String, not: bool },
+    /// conditions in parentheses
+    Group(Vec<Node>),
+    /// column (not) like arg,escape:arg is escaped by '!'('[' is escaped when render for mssql),ignore_case:ilike or lower(column) like lower(arg)
+    Like { column: String, not: bool, arg: Value, escape: bool, ignore_case: bool },
+    /// full text search of columns,rendered per driver
+    FullText(FullText),
+    /// query inside json column,path like "a.b[0]" or "$.a.b[0]",rendered per driver
+    Json { column: String, op: JsonOp, path: String, value: Value },
+    /// column (not) in (sub select) if column is some,else (not) exists (sub select)
+    Sub { column: Option<String>, not: bool, sub: Box<Wrapper> },
+    /// sql with args,placeholders of the sql(write for driver_type,number from 1) will be renumbered
+    Raw { sql: String, args: Vec<Value>, driver_type: DriverType },
+    /// sql rendered as it is
+    Sql(String),
+    /// arg without sql
+    Arg(Value),
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CompareOp {
+    Eq,
+    Ne,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+    Like,
+    NotLike,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum JsonOp {
+    /// value at path = value
+    Eq,
+    /// column contains the json value
+    Contains,
+    /// key at path exists
+    HasKey,
+    /// array at path contains the value
+    ArrayContains,
+}
+
+/// select columns from table,see Wrapper::select()
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Select {
+    pub table_name: String,
+    pub columns: String,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum JoinType {
+    Inner,
+    Left,
+}
+
+/// join table of the Wrapper
+#[derive(Clone)]
+pub struct Join {
+    pub join_type: JoinType,
+    pub table_name: String,
+    pub alias: String,
+    /// table_columns() of the join table
+    pub columns: String,
+    pub on: String,
+    /// formats() of the join table
     pub formats: HashMap<String, fn(arg: &str) -> String>,
 }
 
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OrderBy {
+    pub column: String,
+    pub is_asc: bool,
+    /// nulls first/last,None is the database default
+    pub nulls: Option<NullsOrder>,
+    /// order by full text relevance(most relevant first),the column is ignored
+    pub rank: Option<FullText>,
+}
+
+/// full text search,see Wrapper::full_text()
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FullText {
+    pub columns: Vec<String>,
+    pub query: String,
+    pub mode: FullTextMode,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum FullTextMode {
+    /// mysql natural language mode,pg plainto_tsquery,mssql FREETEXT,sqlite all words
+    Natural,
+    /// mysql boolean mode,pg websearch_to_tsquery,mssql/sqlite raw query syntax
+    Boolean,
+    /// match the whole query as a phrase
+    Phrase,
+}
+
+impl FullText {
+    /// the query arg of the driver
+    fn query_arg(&self, driver_type: &DriverType) -> String {
+        let phrase = || format!("\"{}\"", self.query.replace('"', "\"\""));
+        match (driver_type, self.mode) {
+            (DriverType::Postgres, _) => self.query.clone(),
+            (DriverType::Mysql, FullTextMode::Phrase) => format!("\"{}\"", self.query.replace('"', "")),
+            (DriverType::Sqlite, FullTextMode::Natural) => {
+                self.query.split_whitespace()
+                    .map(|x| format!("\"{}\"", x.replace('"', "\"\"")))
+                    .collect::<Vec<String>>()
+                    .join(" ")
+            }
+            (DriverType::Sqlite, FullTextMode::Phrase) | (DriverType::Mssql, FullTextMode::Phrase) => phrase(),
+            _ => self.query.clone(),
+        }
+    }
+
+    /// to_tsvector(a) or to_tsvector(coalesce(a,'') || ' ' || coalesce(b,''))
+    fn pg_vector(&self) -> String {
+        if self.columns.len() == 1 {
+            return format!("to_tsvector({})", self.columns[0]);
+        }
+        let columns: Vec<String> = self.columns.iter().map(|x| format!("coalesce({},'')", x)).collect();
+        format!("to_tsvector({})", columns.join(" || ' ' || "))
+    }
+
+    fn pg_query(&self) -> &'static str {
+        match self.mode {
+            FullTextMode::Natural => "plainto_tsquery",
+            FullTextMode::Boolean => "websearch_to_tsquery",
+            FullTextMode::Phrase => "phraseto_tsquery",
+        }
+    }
+
+    fn mysql_match(&self, placeholder: &str) -> String {
+        let mode = match self.mode {
+            FullTextMode::Natural => "in natural language mode",
+            _ => "in boolean mode",
+        };
+        format!("match ({}) against ({} {})", self.columns.join(","), placeholder, mode)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum NullsOrder {
+    First,
+    Last,
+}
+
+/// error of Wrapper::order_by_spec()
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SortError {
+    /// the column is not in the whitelist
+    UnknownColumn(String),
+    /// the sort item can not be parsed
+    InvalidSpec(String),
+}
+
+impl std::fmt::Display for SortError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SortError::UnknownColumn(column) => write!(f, "[rbatis] sort column:'{}' is not allowed!", column),
+            SortError::InvalidSpec(item) => write!(f, "[rbatis] invalid sort spec:'{}',for example: 'name,-create_time nulls last'", item),
+        }
+    }
+}
+
+impl std::error::Error for SortError {}
+
+impl From<SortError> for Error {
+    fn from(e: SortError) -> Self {
+        Error::from(e.to_string())
+    }
+}
+
+impl OrderBy {
+    /// parse sort spec like "name,-create_time nulls last",
+    /// '-' is desc,'+' or nothing is asc, every column must be in the whitelist.
+    /// 'nulls first/last' is emulated by 'case when' if the driver not support it
+    pub fn parse_spec(_driver_type: &DriverType, spec: &str, whitelist: &[&str]) -> Result<Vec<OrderBy>, SortError> {
+        let mut result = vec![];
+        if spec.trim().is_empty() {
+            return Ok(result);
+        }
+        for item in spec.split(',') {
+            let words: Vec<&str> = item.split_whitespace().collect();
+            let (column, is_asc) = match words.first() {
+                None => {
+                    return Err(SortError::InvalidSpec(item.to_string()));
+                }
+                Some(v) => {
+                    if let Some(column) = v.strip_prefix('-') {
+                        (column, false)
+                    } else {
+                        (v.strip_prefix('+').unwrap_or(v), true)
+                    }
+                }
+            };
+            if column.is_empty() {
+                return Err(SortError::InvalidSpec(item.to_string()));
+            }
+            if !whitelist.contains(&column) {
+                return Err(SortError::UnknownColumn(column.to_string()));
+            }
+            let nulls = match &words[1..] {
+                [] => None,
+                [n, order] if n.eq_ignore_ascii_case("nulls") => {
+                    if order.eq_ignore_ascii_case("first") {
+                        Some(NullsOrder::First)
+                    } else if order.eq_ignore_ascii_case("last") {
+                        Some(NullsOrder::Last)
+                    } else {
+                        return Err(SortError::InvalidSpec(item.to_string()));
+                    }
+                }
+                _ => {
+                    return Err(SortError::InvalidSpec(item.to_string()));
+                }
+            };
+            result.push(OrderBy {
+                column: column.to_string(),
+                is_asc,
+                nulls,
+                rank: None,
+            });
+        }
+        Ok(result)
+    }
+}
+
 macro_rules! push_sql {
     ($i:expr,$($v:expr,)*) => {
         $($i.push_str($v);)*
     };
 }
 
+impl Deref for Wrapper {
+    type Target = Rendered;
+
+    fn deref(&self) -> &Self::Target {
+        self.rendered.get_or_init(|| {
+            let (sql, args) = self.build(&self.driver_type);
+            Rendered { sql, args }
+        })
+    }
+}
+
 impl Debug for Wrapper {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut formats = HashMap::new();
-        for (k, v) in &self.formats {
-            formats.insert(k.to_string(), v.clone());
-        }
+        let (sql, args) = self.build(&self.driver_type);
         f.debug_struct("Wrapper")
             .field("driver_type", &self.driver_type)
-            .field("sql", &self.sql)
-            .field("args", &self.args)
+            .field("sql", &sql)
+            .field("args", &args)
             //.field("formats", &formats)
             .finish()
     }
 }
 
+impl Node {
+    pub fn is_link(&self) -> bool {
+        match self {
+            Node::And | Node::Or => true,
+            _ => false,
+        }
+    }
+}
+
+impl CompareOp {
+    pub fn sql(&self) -> String {
+        match self {
+            CompareOp::Eq => "=".to_string(),
+            CompareOp::Ne => "<>".to_string(),
+            CompareOp::Gt => ">".to_string(),
+            CompareOp::Ge => ">=".to_string(),
+            CompareOp::Lt => "<".to_string(),
+            CompareOp::Le => "<=".to_string(),
+            CompareOp::Like => crate::sql::TEMPLATE.like.value.to_string(),
+            CompareOp::NotLike => format!("{} {}", crate::sql::TEMPLATE.not.value, crate::sql::TEMPLATE.like.value),
+        }
+    }
+}
+
 impl Wrapper {
     pub fn new(driver_type: &DriverType) -> Self {
         Self {
             driver_type: driver_type.clone(),
-            sql: String::with_capacity(200),
-            args: Vec::with_capacity(5),
             formats: Default::default(),
+            select: None,
+            table_alias: None,
+            joins: vec![],
+            conditions: Vec::with_capacity(5),
+            group_by_columns: vec![],
+            having_conditions: vec![],
+            order_by_columns: vec![],
+            limit_size: None,
+            tail: vec![],
+            replaces: vec![],
+            rendered: OnceCell::new(),
         }
     }
 
     pub fn from(driver_type: &DriverType, sql: &str, args: Vec<Value>) -> Self {
-        Self {
-            driver_type: driver_type.clone(),
+        let mut w = Self::new(driver_type);
+        w.conditions.push(Node::Raw {
             sql: sql.to_string(),
             args: args,
-            formats: HashMap::new(),
+            driver_type: driver_type.clone(),
+        });
+        w
+    }
+
+    /// render sql and args for the driver_type
+    pub fn build(&self, driver_type: &DriverType) -> (String, Vec<Value>) {
+        let mut args = Vec::with_capacity(5);
+        let sql = self.render(driver_type, &mut args);
+        (sql, args)
+    }
+
+    /// render select sql and args for the driver_type,see select()
+    /// for example:
+    ///  rb.new_wrapper_table::<User>().select::<User>(&["id"]).eq("status", 1).build_select(&DriverType::Mysql)
+    ///  // ("select id from user where status = ?", [1])
+    pub fn build_select(&self, driver_type: &DriverType) -> (String, Vec<Value>) {
+        let mut args = Vec::with_capacity(5);
+        let sql = self.render_select(driver_type, &mut args);
+        (sql, args)
+    }
+
+    /// render select sql,placeholders number from args.len()
+    fn render_select(&self, driver_type: &DriverType, args: &mut Vec<Value>) -> String {
+        let where_sql = self.render(driver_type, args);
+        match &self.select {
+            Some(select) => {
+                format!(
+                    "{} {} {} {} {}",
+                    crate::sql::TEMPLATE.select.value,
+                    select.columns,
+                    crate::sql::TEMPLATE.from.value,
+                    self.make_from_sql(&select.table_name),
+                    driver_type.make_where(&where_sql).trim()
+                ).trim().to_string()
+            }
+            None => where_sql,
+        }
+    }
+
+    /// render sql,placeholders number from args.len()
+    fn render(&self, driver_type: &DriverType, args: &mut Vec<Value>) -> String {
+        let mut clauses = Vec::with_capacity(5);
+        let mut where_sql = String::with_capacity(200);
+        self.render_nodes(driver_type, &self.conditions, &mut where_sql, args);
+        let where_sql = trim_where(&where_sql);
+        if !where_sql.is_empty() {
+            clauses.push(where_sql.to_string());
+        }
+        if !self.group_by_columns.is_empty() {
+            clauses.push(format!("{} {}", crate::sql::TEMPLATE.group_by.value, self.group_by_columns.join(",")));
+        }
+        let mut having_sql = String::new();
+        self.render_nodes(driver_type, &self.having_conditions, &mut having_sql, args);
+        let having_sql = trim_link(&having_sql);
+        if !having_sql.is_empty() {
+            clauses.push(format!("{} {}", crate::sql::TEMPLATE.having.value, having_sql));
+        }
+        let mut order_items = Vec::with_capacity(self.order_by_columns.len());
+        for x in &self.order_by_columns {
+            if let Some(ft) = &x.rank {
+                if let Some(item) = self.render_rank(driver_type, ft, args) {
+                    order_items.push(item);
+                }
+                continue;
+            }
+            let mut item = String::new();
+            if let Some(nulls) = x.nulls {
+                if !support_nulls_order(driver_type) {
+                    //emulate 'nulls first/last' with case when
+                    let (first, last) = match nulls {
+                        NullsOrder::First => ("0", "1"),
+                        NullsOrder::Last => ("1", "0"),
+                    };
+                    push_sql!(item,"case when ",&x.column," is null then ",first," else ",last," end,",);
+                }
+            }
+            if x.is_asc {
+                push_sql!(item,&x.column," ",crate::sql::TEMPLATE.asc.value,);
+            } else {
+                push_sql!(item,&x.column," ",crate::sql::TEMPLATE.desc.value,);
+            }
+            if let Some(nulls) = x.nulls {
+                if support_nulls_order(driver_type) {
+                    match nulls {
+                        NullsOrder::First => item.push_str(" nulls first"),
+                        NullsOrder::Last => item.push_str(" nulls last"),
+                    }
+                }
+            }
+            order_items.push(item);
+        }
+        if !order_items.is_empty() {
+            clauses.push(format!("{}{}", crate::sql::TEMPLATE.order_by.right_space, order_items.join(",")));
+        }
+        if let Some(limit) = self.limit_size {
+            match driver_type {
+                DriverType::Mssql => {
+                    //offset ... fetch next ... must after order by
+                    if order_items.is_empty() {
+                        clauses.push(format!("{}(select null)", crate::sql::TEMPLATE.order_by.right_space));
+                    }
+                    clauses.push(driver_type.page_limit_sql(0, limit).unwrap_or_default().trim().to_string());
+                }
+                _ => {
+                    clauses.push(format!("{} {}", crate::sql::TEMPLATE.limit.value, limit));
+                }
+            }
+        }
+        let mut sql = clauses.join(" ");
+        self.render_nodes(driver_type, &self.tail, &mut sql, args);
+        for (from, to) in &self.replaces {
+            sql = sql.replace(from, to);
+        }
+        sql
+    }
+
+    /// the rendered sql of self.driver_type
+    pub fn sql(&self) -> String {
+        self.build(&self.driver_type).0
+    }
+
+    /// the rendered args of self.driver_type
+    pub fn args(&self) -> Vec<Value> {
+        self.build(&self.driver_type).1
+    }
+
+    /// is rendered sql empty
+    pub fn is_empty(&self) -> bool {
+        is_empty_nodes(&self.conditions)
+            && self.group_by_columns.is_empty()
+            && is_empty_nodes(&self.having_conditions)
+            && self.order_by_columns.is_empty()
+            && self.limit_size.is_none()
+            && is_empty_nodes(&self.tail)
+    }
+
+    pub fn driver_type(&self) -> &DriverType {
+        &self.driver_type
+    }
+
+    pub fn formats(&self) -> &HashMap<String, fn(arg: &str) -> String> {
+        &self.formats
+    }
+
+    /// select of the sub query,see select()
+    pub fn get_select(&self) -> Option<&Select> {
+        self.select.as_ref()
+    }
+
+    /// alias of the main table,see alias()
+    pub fn table_alias(&self) -> Option<&str> {
+        self.table_alias.as_deref()
+    }
+
+    pub fn joins(&self) -> &[Join] {
+        &self.joins
+    }
+
+    /// where conditions
+    pub fn conditions(&self) -> &[Node] {
+        &self.conditions
+    }
+
+    pub fn group_by_columns(&self) -> &[String] {
+        &self.group_by_columns
+    }
+
+    pub fn having_conditions(&self) -> &[Node] {
+        &self.having_conditions
+    }
+
+    pub fn order_by_columns(&self) -> &[OrderBy] {
+        &self.order_by_columns
+    }
+
+    pub fn limit_size(&self) -> Option<u64> {
+        self.limit_size
+    }
+
+    /// sql/args pushed after group by,having,order by or limit
+    pub fn tail(&self) -> &[Node] {
+        &self.tail
+    }
+
+    fn render_nodes(&self, driver_type: &DriverType, nodes: &[Node], sql: &mut String, args: &mut Vec<Value>) {
+        //the first 'and'/'or' before next condition
+        let mut link: Option<&Node> = None;
+        for node in nodes {
+            match node {
+                Node::And | Node::Or => {
+                    if link.is_none() {
+                        link = Some(node);
+                    }
+                }
+                Node::Arg(v) => {
+                    args.push(v.clone());
+                }
+                Node::Sql(v) => {
+                    if let Some(link) = link.take() {
+                        push_link(sql, link);
+                    }
+                    sql.push_str(v);
+                }
+                Node::Raw { sql: raw_sql, args: raw_args, driver_type: raw_driver_type } => {
+                    if let Some(link) = link.take() {
+                        push_link(sql, link);
+                    }
+                    sql.push_str(&convert_placeholders(raw_sql, raw_driver_type, driver_type, args.len()));
+                    args.extend(raw_args.iter().cloned());
+                }
+                Node::Group(nodes) => {
+                    let mut group_sql = String::new();
+                    self.render_nodes(driver_type, nodes, &mut group_sql, args);
+                    let group_sql = trim_link(&group_sql);
+                    if group_sql.is_empty() {
+                        continue;
+                    }
+                    push_link(sql, link.take().unwrap_or(&Node::And));
+                    push_sql!(sql,"(",group_sql,")",);
+                }
+                _ => {
+                    push_link(sql, link.take().unwrap_or(&Node::And));
+                    self.render_condition(driver_type, node, sql, args);
+                }
+            }
         }
     }
 
+    fn render_condition(&self, driver_type: &DriverType, node: &Node, sql: &mut String, args: &mut Vec<Value>) {
+        match node {
+            Node::Compare { column, op, arg } => {
+                push_sql!(sql,column," ",&op.sql()," ",);
+                self.push_placeholder(driver_type, column, arg, sql, args);
+            }
+            Node::Between { column, not, min, max } => {
+                sql.push_str(column);
+                if *not {
+                    push_sql!(sql," ",crate::sql::TEMPLATE.not.value,);
+                }
+                push_sql!(sql," ",crate::sql::TEMPLATE.between.value," ",);
+                self.push_placeholder(driver_type, column, min, sql, args);
+                push_sql!(sql," ",crate::sql::TEMPLATE.and.value," ",);
+                self.push_placeholder(driver_type, column, max, sql, args);
+            }
+            Node::In { column, not, args: in_args } => {
+                if in_args.is_empty() {
+                    //in () is always false,not in () is always true
+                    sql.push_str(if *not { "1 = 1" } else { "1 = 0" });
+                    return;
+                }
+                if driver_type.eq(&DriverType::Postgres)
+                    && in_args.len() >= ANY_ARRAY_MIN_LEN
+                    && !self.formats.contains_key(column.as_str())
+                    && is_same_type_array(in_args) {
+                    //send the list as one array arg
+                    let placeholder = push_arg_placeholder(driver_type, Value::Array(in_args.clone()), args);
+                    if *not {
+                        push_sql!(sql,column," <> all(",&placeholder,")",);
+                    } else {
+                        push_sql!(sql,column," = any(",&placeholder,")",);
+                    }
+                    return;
+                }
+                if in_args.len() > max_in_len(driver_type)
+                    && !self.formats.contains_key(column.as_str())
+                    && in_args.iter().all(|x| x.is_i64() || x.is_u64()) {
+                    //integers are safe to inline,the list not use any param
+                    sql.push_str(column);
+                    if *not {
+                        push_sql!(sql," ",crate::sql::TEMPLATE.not.value,);
+                    }
+                    push_sql!(sql," ",crate::sql::TEMPLATE.r#in.value," (",);
+                    for x in in_args {
+                        push_sql!(sql," ",&x.to_string()," ,",);
+                    }
+                    sql.pop();
+                    sql.push_str(")");
+                    return;
+                }
+                //the params over the limit of driver is checked when bind,see check_params()
+                self.render_in_list(driver_type, column, *not, in_args, sql, args);
+            }
+            Node::IsNull { column, not } => {
+                push_sql!(sql,column,crate::sql::TEMPLATE.is.left_right_space,);
+                if *not {
+                    sql.push_str(crate::sql::TEMPLATE.not.right_space);
+                }
+                sql.push_str(crate::sql::TEMPLATE.null.value);
+            }
+            Node::Sub { column, not, sub } => {
+                if let Some(column) = column {
+                    push_sql!(sql,column," ",);
+                }
+                if *not {
+                    sql.push_str(crate::sql::TEMPLATE.not.right_space);
+                }
+                match column {
+                    Some(_) => {
+                        sql.push_str(crate::sql::TEMPLATE.r#in.value);
+                    }
+                    None => {
+                        sql.push_str(crate::sql::TEMPLATE.exists.value);
+                    }
+                }
+                push_sql!(sql," (",&sub.render_select(driver_type, args),")",);
+            }
+            Node::Like { column, not, arg, escape, ignore_case } => {
+                let ilike = *ignore_case && driver_type.eq(&DriverType::Postgres);
+                if *ignore_case && !ilike {
+                    push_sql!(sql,"lower(",column,")",);
+                } else {
+                    sql.push_str(column);
+                }
+                if *not {
+                    push_sql!(sql," ",crate::sql::TEMPLATE.not.value,);
+                }
+                if ilike {
+                    sql.push_str(" ilike ");
+                } else {
+                    push_sql!(sql," ",crate::sql::TEMPLATE.like.value," ",);
+                }
+                let mssql_arg;
+                let arg = match (driver_type, arg.as_str()) {
+                    //'[' is a wildcard of mssql only
+                    (DriverType::Mssql, Some(v)) if *escape => {
+                        mssql_arg = json!(v.replace('[', "!["));
+                        &mssql_arg
+                    }
+                    _ => arg,
+                };
+                if *ignore_case && !ilike {
+                    sql.push_str("lower(");
+                    self.push_placeholder(driver_type, column, arg, sql, args);
+                    sql.push_str(")");
+                } else {
+                    self.push_placeholder(driver_type, column, arg, sql, args);
+                }
+                if *escape {
+                    //not backslash,it is not an escape char of mysql(NO_BACKSLASH_ESCAPES) string
+                    sql.push_str(" escape '!'");
+                }
+            }
+            Node::Json { column, op, path, value } => {
+                self.render_json(driver_type, column, *op, path, value, sql, args);
+            }
+            Node::FullText(ft) => {
+                let query = json!(ft.query_arg(driver_type));
+                match driver_type {
+                    DriverType::Mysql => {
+                        let placeholder = push_arg_placeholder(driver_type, query, args);
+                        sql.push_str(&ft.mysql_match(&placeholder));
+                    }
+                    DriverType::Postgres => {
+                        let placeholder = push_arg_placeholder(driver_type, query, args);
+                        push_sql!(sql,&ft.pg_vector()," @@ ",ft.pg_query(),"(",&placeholder,")",);
+                    }
+                    DriverType::Sqlite => {
+                        let items: Vec<String> = ft.columns.iter()
+                            .map(|x| format!("{} match {}", x, push_arg_placeholder(driver_type, query.clone(), args)))
+                            .collect();
+                        if items.len() == 1 {
+                            sql.push_str(&items[0]);
+                        } else {
+                            push_sql!(sql,"(",&items.join(" or "),")",);
+                        }
+                    }
+                    _ => {
+                        let placeholder = push_arg_placeholder(driver_type, query, args);
+                        let function = match ft.mode {
+                            FullTextMode::Natural => "freetext",
+                            _ => "contains",
+                        };
+                        push_sql!(sql,function,"((",&ft.columns.join(","),"), ",&placeholder,")",);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn render_json(&self, driver_type: &DriverType, column: &str, op: JsonOp, path: &str, value: &Value, sql: &mut String, args: &mut Vec<Value>) {
+        let json_path = json_path(path);
+        match (driver_type, op) {
+            (DriverType::Postgres, JsonOp::Eq) => {
+                let path_arg = push_arg_placeholder(driver_type, json!(pg_json_path(&json_path)), args);
+                let value_arg = push_arg_placeholder(driver_type, json!(json_text(value)), args);
+                push_sql!(sql,column," #>> ",&path_arg,"::text[] = ",&value_arg,);
+            }
+            (DriverType::Postgres, JsonOp::Contains) => {
+                let value_arg = push_arg_placeholder(driver_type, json!(value.to_string()), args);
+                push_sql!(sql,column," @> ",&value_arg,"::jsonb",);
+            }
+            (DriverType::Postgres, JsonOp::HasKey) => {
+                let pg_path = pg_json_path(&json_path);
+                let keys: Vec<&str> = pg_path.trim_start_matches('{').trim_end_matches('}').split(',').collect();
+                if keys.len() > 1 {
+                    let parent = format!("{{{}}}", keys[..keys.len() - 1].join(","));
+                    let path_arg = push_arg_placeholder(driver_type, json!(parent), args);
+                    let key_arg = push_arg_placeholder(driver_type, json!(keys[keys.len() - 1]), args);
+                    push_sql!(sql,"(",column," #> ",&path_arg,"::text[]) ? ",&key_arg,);
+                } else {
+                    let key_arg = push_arg_placeholder(driver_type, json!(keys[0]), args);
+                    push_sql!(sql,column," ? ",&key_arg,);
+                }
+            }
+            (DriverType::Postgres, JsonOp::ArrayContains) => {
+                let array = json!(json!([value]).to_string());
+                if json_path == "$" {
+                    let value_arg = push_arg_placeholder(driver_type, array, args);
+                    push_sql!(sql,column," @> ",&value_arg,"::jsonb",);
+                } else {
+                    let path_arg = push_arg_placeholder(driver_type, json!(pg_json_path(&json_path)), args);
+                    let value_arg = push_arg_placeholder(driver_type, array, args);
+                    push_sql!(sql,column," #> ",&path_arg,"::text[] @> ",&value_arg,"::jsonb",);
+                }
+            }
+            (DriverType::Mysql, JsonOp::Eq) => {
+                let path_arg = push_arg_placeholder(driver_type, json!(json_path), args);
+                let value_arg = push_arg_placeholder(driver_type, value.clone(), args);
+                push_sql!(sql,"json_unquote(json_extract(",column,", ",&path_arg,")) = ",&value_arg,);
+            }
+            (DriverType::Mysql, JsonOp::Contains) => {
+                let value_arg = push_arg_placeholder(driver_type, json!(value.to_string()), args);
+                push_sql!(sql,"json_contains(",column,", ",&value_arg,")",);
+            }
+            (DriverType::Mysql, JsonOp::HasKey) => {
+                let path_arg = push_arg_placeholder(driver_type, json!(json_path), args);
+                push_sql!(sql,"json_contains_path(",column,", 'one', ",&path_arg,")",);
+            }
+            (DriverType::Mysql, JsonOp::ArrayContains) => {
+                let value_arg = push_arg_placeholder(driver_type, json!(value.to_string()), args);
+                let path_arg = push_arg_placeholder(driver_type, json!(json_path), args);
+                push_sql!(sql,"json_contains(",column,", ",&value_arg,", ",&path_arg,")",);
+            }
+            (_, JsonOp::Contains) => {
+                //no json containment,compare every leaf value of the json
+                let mut items = vec![];
+                json_leaves(&json_path, value, &mut items);
+                let mut contains_sql = String::new();
+                for (index, (leaf_op, leaf_path, leaf_value)) in items.iter().enumerate() {
+                    if index > 0 {
+                        push_sql!(contains_sql," ",crate::sql::TEMPLATE.and.value," ",);
+                    }
+                    self.render_json(driver_type, column, *leaf_op, leaf_path, leaf_value, &mut contains_sql, args);
+                }
+                match items.len() {
+                    0 => sql.push_str("1 = 1"),
+                    1 => sql.push_str(&contains_sql),
+                    _ => {
+                        push_sql!(sql,"(",&contains_sql,")",);
+                    }
+                }
+            }
+            (DriverType::Mssql, JsonOp::Eq) => {
+                let path_arg = push_arg_placeholder(driver_type, json!(json_path), args);
+                let value_arg = push_arg_placeholder(driver_type, json!(json_text(value)), args);
+                push_sql!(sql,"json_value(",column,", ",&path_arg,") = ",&value_arg,);
+            }
+            (DriverType::Mssql, JsonOp::HasKey) => {
+                let path_arg = push_arg_placeholder(driver_type, json!(json_path), args);
+                push_sql!(sql,"json_path_exists(",column,", ",&path_arg,") = 1",);
+            }
+            (DriverType::Mssql, JsonOp::ArrayContains) => {
+                let path_arg = push_arg_placeholder(driver_type, json!(json_path), args);
+                let value_arg = push_arg_placeholder(driver_type, json!(json_text(value)), args);
+                push_sql!(sql,"exists (select 1 from openjson(",column,", ",&path_arg,") where value = ",&value_arg,")",);
+            }
+            (_, JsonOp::Eq) => {
+                let path_arg = push_arg_placeholder(driver_type, json!(json_path), args);
+                let value_arg = push_arg_placeholder(driver_type, value.clone(), args);
+                push_sql!(sql,"json_extract(",column,", ",&path_arg,") = ",&value_arg,);
+            }
+            (_, JsonOp::HasKey) => {
+                let path_arg = push_arg_placeholder(driver_type, json!(json_path), args);
+                push_sql!(sql,"json_type(",column,", ",&path_arg,") is not null",);
+            }
+            (_, JsonOp::ArrayContains) => {
+                let path_arg = push_arg_placeholder(driver_type, json!(json_path), args);
+                let value_arg = push_arg_placeholder(driver_type, value.clone(), args);
+                push_sql!(sql,"exists (select 1 from json_each(",column,", ",&path_arg,") where value = ",&value_arg,")",);
+            }
+        }
+    }
+
+    /// order by sql of full text relevance,None if the driver not support
+    fn render_rank(&self, driver_type: &DriverType, ft: &FullText, args: &mut Vec<Value>) -> Option<String> {
+        match driver_type {
+            DriverType::Mysql => {
+                let placeholder = push_arg_placeholder(driver_type, json!(ft.query_arg(driver_type)), args);
+                Some(format!("{} {}", ft.mysql_match(&placeholder), crate::sql::TEMPLATE.desc.value))
+            }
+            DriverType::Postgres => {
+                let placeholder = push_arg_placeholder(driver_type, json!(ft.query_arg(driver_type)), args);
+                Some(format!("ts_rank({}, {}({})) {}", ft.pg_vector(), ft.pg_query(), placeholder, crate::sql::TEMPLATE.desc.value))
+            }
+            //fts5 rank is smaller for better matches
+            DriverType::Sqlite => Some(format!("rank {}", crate::sql::TEMPLATE.asc.value)),
+            //mssql rank need CONTAINSTABLE
+            _ => None,
+        }
+    }
+
+    /// * in ( ? , ? ),every value is an arg
+    fn render_in_list(&self, driver_type: &DriverType, column: &str, not: bool, in_args: &[Value], sql: &mut String, args: &mut Vec<Value>) {
+        sql.push_str(column);
+        if not {
+            push_sql!(sql," ",crate::sql::TEMPLATE.not.value,);
+        }
+        push_sql!(sql," ",crate::sql::TEMPLATE.r#in.value," (",);
+        for x in in_args {
+            sql.push_str(" ");
+            self.push_placeholder(driver_type, column, x, sql, args);
+            sql.push_str(" ,");
+        }
+        sql.pop();
+        sql.push_str(")");
+    }
+
+    fn push_placeholder(&self, driver_type: &DriverType, column: &str, arg: &Value, sql: &mut String, args: &mut Vec<Value>) {
+        let mut convert_column = String::new();
+        driver_type.stmt_convert(args.len(), &mut convert_column);
+        self.do_format_column(column, &mut convert_column);
+        sql.push_str(&convert_column);
+        args.push(arg.clone());
+    }
+
+    fn push_node(mut self, node: Node) -> Self {
+        self.rendered.take();
+        self.conditions.push(node);
+        self
+    }
+
+    /// raw sql/arg pushed after group by,having,order by or limit will be rendered at the end
+    fn push_raw_node(mut self, node: Node) -> Self {
+        if self.group_by_columns.is_empty()
+            && self.having_conditions.is_empty()
+            && self.order_by_columns.is_empty()
+            && self.limit_size.is_none()
+            && self.tail.is_empty() {
+            self.rendered.take();
+            self.conditions.push(node);
+        } else {
+            self.rendered.take();
+            self.tail.push(node);
+        }
+        self
+    }
+
+    /// replace all nodes with one Raw node
+    fn flatten(mut self, sql: String, args: Vec<Value>) -> Self {
+        self.rendered.take();
+        self.conditions = vec![Node::Raw {
+            sql,
+            args,
+            driver_type: self.driver_type.clone(),
+        }];
+        self.group_by_columns.clear();
+        self.having_conditions.clear();
+        self.order_by_columns.clear();
+        self.limit_size = None;
+        self.tail.clear();
+        self.replaces.clear();
+        self
+    }
+
+    /// replace 'from' to 'to' of the rendered sql
     pub fn trim_value(mut self, from: &str, to: &str) -> Self {
-        self.sql = self.sql.replace(from, to);
+        self.rendered.take();
+        self.replaces.push((from.to_string(), to.to_string()));
         self
     }
 
     pub fn set_formats(mut self, formats: HashMap<String, fn(arg: &str) -> String>) -> Self {
+        self.rendered.take();
         self.formats = formats;
         self
     }
 
+    /// set format of the column(or "table_name" for dyn table name)
+    /// for example:
+    ///  w.set_format("table_name", |_| "biz_activity_2021".to_string())
+    pub fn set_format(mut self, column: &str, format: fn(arg: &str) -> String) -> Self {
+        self.rendered.take();
+        self.formats.insert(column.to_string(), format);
+        self
+    }
+
     /// link left Wrapper to this Wrapper
     /// for Example:
     /// let w = Wrapper::new(&DriverType::Postgres).push_sql("(").eq("a", "1").push_sql(")");
     /// let w2 = Wrapper::new(&DriverType::Postgres).eq("b", "2")
     /// .and()
     /// .push_wrapper(&w);
-    /// println!("sql:{:?}", w2.sql.as_str());  // sql:"b = ? and (a = ?)"
-    /// println!("arg:{:?}", w2.args.clone()); // arg:[String("2"), String("1")]
+    /// println!("sql:{:?}", w2.sql());  // sql:"b = $1 and (a = $2)"
+    /// println!("arg:{:?}", w2.args()); // arg:[String("2"), String("1")]
     ///
-    pub fn push_wrapper(self, arg: &Wrapper) -> Self {
-        self.push(&arg.sql, &arg.args)
+    pub fn push_wrapper(mut self, arg: &Wrapper) -> Self {
+        self.rendered.take();
+        self.joins.extend(arg.joins.iter().cloned());
+        self.conditions.extend(arg.conditions.iter().cloned());
+        self.group_by_columns.extend(arg.group_by_columns.iter().cloned());
+        self.having_conditions.extend(arg.having_conditions.iter().cloned());
+        self.order_by_columns.extend(arg.order_by_columns.iter().cloned());
+        if arg.limit_size.is_some() {
+            self.limit_size = arg.limit_size;
+        }
+        self.tail.extend(arg.tail.iter().cloned());
+        self.replaces.extend(arg.replaces.iter().cloned());
+        self
     }
 
-    /// push sql,args into self
-    pub fn push<T>(mut self, sql: &str, args: &[T]) -> Self
+    /// push sql,args into self,placeholders of the sql number from 1
+    /// for example:
+    ///  push("a = $1 and b = $2", &[1, 2])
+    pub fn push<T>(self, sql: &str, args: &[T]) -> Self
         where
             T: Serialize,
     {
-        let mut new_sql = sql.to_string();
-        if self.driver_type.is_number_type() {
-            let self_arg_len = self.args.len();
-            for index in 0..args.len() {
-                let mut convert_column = String::new();
-                self.driver_type.stmt_convert(index, &mut convert_column);
-
-                let mut convert_column_new = String::new();
-                self.driver_type.stmt_convert(index + args.len(), &mut convert_column_new);
-                new_sql = new_sql.replace(
-                    convert_column.as_str(),
-                    convert_column_new.as_str(),
-                );
-            }
-            for index in args.len()..self_arg_len {
-                let mut convert_column = String::new();
-                self.driver_type.stmt_convert(index, &mut convert_column);
-
-                let mut convert_column_new = String::new();
-                self.driver_type.stmt_convert(index + args.len(), &mut convert_column_new);
-                new_sql = new_sql.replace(
-                    convert_column.as_str(),
-                    convert_column_new.as_str(),
-                );
-            }
-        }
-        self.sql.push_str(new_sql.as_str());
+        let args = match json!(args) {
+            Value::Array(arr) => arr,
+            _ => vec![],
+        };
+        let driver_type = self.driver_type.clone();
+        self.push_raw_node(Node::Raw {
+            sql: sql.to_string(),
+            args,
+            driver_type,
+        })
+    }
 
-        let args = json!(args);
-        if args.is_null() {
-            return self;
-        }
-        let args = args.as_array().unwrap();
-        for x in args {
-            self.args.push(x.to_owned());
-        }
-        self
+    /// push the conditions of a filter struct(#[derive(WrapperFilter)]),None fields will be skip
+    /// for example:
+    ///  let w = rb.new_wrapper().filter(&query).order_by(false, &["create_time"]);
+    pub fn filter<F>(self, filter: &F) -> Self
+        where
+            F: WrapperFilter,
+    {
+        filter.filter(self)
     }
 
     /// do method,if test is true
@@ -199,17 +1092,18 @@ impl Wrapper {
         return default(self);
     }
 
-    pub fn set_sql(mut self, sql: &str) -> Self {
-        self.sql = sql.to_string();
-        self
+    /// replace all sql,args will be keep
+    pub fn set_sql(self, sql: &str) -> Self {
+        let args = self.args();
+        self.flatten(sql.to_string(), args)
     }
 
-    pub fn push_sql(mut self, sql: &str) -> Self {
-        self.sql.push_str(sql);
-        self
+    pub fn push_sql(self, sql: &str) -> Self {
+        self.push_raw_node(Node::Sql(sql.to_string()))
     }
 
-    pub fn set_args<T>(mut self, args: &[T]) -> Self
+    /// replace all args,sql will be keep
+    pub fn set_args<T>(self, args: &[T]) -> Self
         where
             T: Serialize,
     {
@@ -218,68 +1112,88 @@ impl Wrapper {
             return self;
         }
         if v.is_array() {
-            self.args = v.as_array().unwrap_or(&vec![]).to_owned();
+            let sql = self.sql();
+            return self.flatten(sql, v.as_array().unwrap_or(&vec![]).to_owned());
         }
         self
     }
 
-    pub fn push_arg<T>(mut self, arg: T) -> Self
+    pub fn push_arg<T>(self, arg: T) -> Self
         where
             T: Serialize,
     {
-        let v = json!(arg);
-        self.args.push(v);
-        self
+        self.push_raw_node(Node::Arg(json!(arg)))
     }
 
-    pub fn pop_arg(mut self) -> Self {
-        self.args.pop();
-        self
+    pub fn pop_arg(self) -> Self {
+        let (sql, mut args) = self.build(&self.driver_type);
+        args.pop();
+        self.flatten(sql, args)
     }
 
     pub fn not_allow_add_and_on_end(&self) -> bool {
-        let sql = self.sql.trim_end();
-        if sql.is_empty() {
-            return true;
-        }
-        sql.ends_with(crate::sql::TEMPLATE.r#where.left_space)
-            || sql.ends_with(crate::sql::TEMPLATE.and.left_space)
-            || sql.ends_with(crate::sql::TEMPLATE.or.left_space)
-            || sql.ends_with("(")
-            || sql.ends_with(",")
-            || sql.ends_with("=")
-            || sql.ends_with("+")
-            || sql.ends_with("-")
-            || sql.ends_with("*")
-            || sql.ends_with("/")
-            || sql.ends_with("%")
-            || sql.ends_with("^")
-            || sql.ends_with(">")
-            || sql.ends_with("<")
-            || sql.ends_with("&")
-            || sql.ends_with("|")
+        let mut sql = String::new();
+        self.render_nodes(&self.driver_type, &self.conditions, &mut sql, &mut vec![]);
+        not_allow_link(&sql)
     }
 
     /// link wrapper sql, if end with where , do nothing
-    pub fn and(mut self) -> Self {
-        if !self.not_allow_add_and_on_end() {
-            self.sql
-                .push_str(&crate::sql::TEMPLATE.and.left_right_space);
-        }
-        self
+    pub fn and(self) -> Self {
+        self.push_node(Node::And)
     }
 
     /// link wrapper sql, if end with where , do nothing
-    pub fn or(mut self) -> Self {
-        if !self.not_allow_add_and_on_end() {
-            self.sql.push_str(&crate::sql::TEMPLATE.or.left_right_space);
+    pub fn or(self) -> Self {
+        self.push_node(Node::Or)
+    }
+
+    /// add an "and" group, the sql build by f will be wrapped in parentheses, empty group will be skip
+    /// for example:
+    ///  w.eq("a", 1).and_group(|w| w.eq("b", 2).or().eq("c", 3))  // "a = ? and (b = ? or c = ?)"
+    pub fn and_group<F>(self, f: F) -> Self
+        where
+            F: FnOnce(Wrapper) -> Wrapper,
+    {
+        self.push_group(Node::And, f)
+    }
+
+    /// add an "or" group, the sql build by f will be wrapped in parentheses, empty group will be skip
+    /// for example:
+    ///  w.eq("a", 1).or_group(|w| w.eq("b", 2).eq("c", 3))  // "a = ? or (b = ? and c = ?)"
+    pub fn or_group<F>(self, f: F) -> Self
+        where
+            F: FnOnce(Wrapper) -> Wrapper,
+    {
+        self.push_group(Node::Or, f)
+    }
+
+    /// wrap all conditions in parentheses,the conditions added after will not be mixed into an 'or'
+    /// for example:
+    ///  w.eq("a", 1).or().eq("b", 2).group().eq("c", 3)  // "(a = ? or b = ?) and c = ?"
+    pub fn group(mut self) -> Self {
+        self.rendered.take();
+        let conditions = std::mem::take(&mut self.conditions);
+        if !conditions.iter().all(|x| x.is_link()) {
+            self.conditions.push(Node::Group(conditions));
         }
         self
     }
 
+    fn push_group<F>(self, link: Node, f: F) -> Self
+        where
+            F: FnOnce(Wrapper) -> Wrapper,
+    {
+        let group = f(Wrapper::new(&self.driver_type));
+        if group.conditions.iter().all(|x| x.is_link()) {
+            return self;
+        }
+        self.push_node(link).push_node(Node::Group(group.conditions))
+    }
+
     pub fn having(mut self, sql_having: &str) -> Self {
-        self = self.and();
-        push_sql!(self.sql," ",crate::sql::TEMPLATE.having.value," ",sql_having," ",);
+        self.rendered.take();
+        self.having_conditions.push(Node::And);
+        self.having_conditions.push(Node::Sql(sql_having.to_string()));
         self
     }
 
@@ -305,16 +1219,29 @@ impl Wrapper {
         for (k, v) in map {
             self = self.eq(k.as_str(), v);
             if (index + 1) != len {
-                self.sql.push_str(" , ");
+                self = self.push_node(Node::Sql(" , ".to_string()));
                 index += 1;
             }
         }
         self
     }
 
-    ///format column
+    ///format column,qualified column(alias.column) of the join table use formats of the join table
     pub fn do_format_column(&self, column: &str, data: &mut String) {
-        let source = self.formats.get(column);
+        let mut source = self.formats.get(column);
+        if source.is_none() {
+            if let Some(index) = column.find('.') {
+                let (alias, name) = (&column[..index], &column[index + 1..]);
+                match self.joins.iter().find(|x| x.alias == alias) {
+                    Some(join) => {
+                        source = join.formats.get(name);
+                    }
+                    None => {
+                        source = self.formats.get(name);
+                    }
+                }
+            }
+        }
         match source {
             Some(f) => {
                 *data = f(&data);
@@ -323,391 +1250,599 @@ impl Wrapper {
         }
     }
 
-    /// equal
+    /// set alias of the main table
     /// for example:
-    ///  eq("a",1) " a = 1 "
-    pub fn eq<T>(mut self, column: &str, obj: T) -> Self
+    ///  rb.new_wrapper_table::<BizActivity>().alias("a")  // select ... from biz_activity a
+    pub fn alias(mut self, alias: &str) -> Self {
+        self.rendered.take();
+        self.table_alias = Some(alias.to_string());
+        self
+    }
+
+    /// left join table T,alias is T::table_name()
+    /// for example:
+    ///  w.alias("a").left_join::<User>("a.user_id = user.id")
+    pub fn left_join<T>(self, on: &str) -> Self
         where
-            T: Serialize,
+            T: CRUDTable,
     {
-        self = self.and();
-        let mut convert_column = String::new();
-        self.driver_type.stmt_convert(self.args.len(), &mut convert_column);
-        self.do_format_column(column, &mut convert_column);
-        push_sql!(self.sql,column," = ",convert_column.as_str(),);
-        self.args.push(json!(obj));
-        self
+        self.join::<T>(JoinType::Left, &T::table_name(), on)
     }
 
-    /// not equal
-    pub fn ne<T>(mut self, column: &str, obj: T) -> Self
+    /// left join table T as alias
+    /// for example:
+    ///  w.alias("a").left_join_as::<User>("u", "a.user_id = u.id")
+    pub fn left_join_as<T>(self, alias: &str, on: &str) -> Self
         where
-            T: Serialize,
+            T: CRUDTable,
     {
-        self = self.and();
-        let mut convert_column = String::new();
-        self.driver_type.stmt_convert(self.args.len(), &mut convert_column);
-        self.do_format_column(column, &mut convert_column);
-        push_sql!(self.sql,column," <> ",convert_column.as_str(),);
-        self.args.push(json!(obj));
-        self
+        self.join::<T>(JoinType::Left, alias, on)
     }
 
-    pub fn order_by(mut self, is_asc: bool, columns: &[&str]) -> Self {
-        let len = columns.len();
-        if len == 0 {
-            return self;
-        }
-        let mut index = 0;
-        self.sql = self
-            .sql
-            .trim_end()
-            .trim_end_matches(crate::sql::TEMPLATE.r#where.left_space)
-            .trim_end_matches(crate::sql::TEMPLATE.and.left_space)
-            .trim_end_matches(crate::sql::TEMPLATE.or.left_space)
-            .to_string();
-        self.sql
-            .push_str(&crate::sql::TEMPLATE.order_by.left_right_space);
-        for x in columns {
-            if is_asc {
-                push_sql!(self.sql,x," ",crate::sql::TEMPLATE.asc.value,);
-            } else {
-                push_sql!(self.sql,x," ",crate::sql::TEMPLATE.desc.value,);
+    /// inner join table T,alias is T::table_name()
+    pub fn inner_join<T>(self, on: &str) -> Self
+        where
+            T: CRUDTable,
+    {
+        self.join::<T>(JoinType::Inner, &T::table_name(), on)
+    }
+
+    /// inner join table T as alias
+    pub fn inner_join_as<T>(self, alias: &str, on: &str) -> Self
+        where
+            T: CRUDTable,
+    {
+        self.join::<T>(JoinType::Inner, alias, on)
+    }
+
+    pub fn join<T>(mut self, join_type: JoinType, alias: &str, on: &str) -> Self
+        where
+            T: CRUDTable,
+    {
+        self.rendered.take();
+        self.joins.push(Join {
+            join_type,
+            table_name: T::table_name(),
+            alias: alias.to_string(),
+            columns: T::table_columns(),
+            on: on.to_string(),
+            formats: T::formats(&self.driver_type),
+        });
+        self
+    }
+
+    /// alias of the main table,default is table_name
+    pub fn get_alias<'a>(&'a self, table_name: &'a str) -> &'a str {
+        self.table_alias.as_deref().unwrap_or(table_name)
+    }
+
+    /// gen sql: table_name alias
+    pub fn make_table_sql(&self, table_name: &str) -> String {
+        let mut sql = table_name.to_string();
+        if let Some(alias) = &self.table_alias {
+            if alias != table_name {
+                push_sql!(sql," ",alias,);
             }
-            if (index + 1) != len {
-                self.sql.push_str(",");
-                index += 1;
+        }
+        sql
+    }
+
+    /// gen sql: table_name alias left join t1 alias1 on ... inner join t2 alias2 on ...
+    pub fn make_from_sql(&self, table_name: &str) -> String {
+        let mut sql = self.make_table_sql(table_name);
+        for x in &self.joins {
+            match x.join_type {
+                JoinType::Inner => {
+                    push_sql!(sql," ",crate::sql::TEMPLATE.inner_join.value," ",);
+                }
+                JoinType::Left => {
+                    push_sql!(sql," ",crate::sql::TEMPLATE.left_join.value," ",);
+                }
+            }
+            sql.push_str(&x.table_name);
+            if x.alias != x.table_name {
+                push_sql!(sql," ",&x.alias,);
             }
+            push_sql!(sql," ",crate::sql::TEMPLATE.on.value," ",&x.on,);
+        }
+        sql
+    }
+
+    fn compare<T>(self, column: &str, op: CompareOp, obj: T) -> Self
+        where
+            T: Serialize,
+    {
+        self.push_node(Node::Compare {
+            column: column.to_string(),
+            op,
+            arg: json!(obj),
+        })
+    }
+
+    /// equal
+    /// for example:
+    ///  eq("a",1) " a = 1 "
+    pub fn eq<C, T>(self, column: C, obj: T) -> Self
+        where
+            C: ColumnArg<T>,
+            T: Serialize,
+    {
+        self.compare(column.column_name(), CompareOp::Eq, obj)
+    }
+
+    /// not equal
+    pub fn ne<C, T>(self, column: C, obj: T) -> Self
+        where
+            C: ColumnArg<T>,
+            T: Serialize,
+    {
+        self.compare(column.column_name(), CompareOp::Ne, obj)
+    }
+
+    pub fn order_by(mut self, is_asc: bool, columns: &[&str]) -> Self {
+        for x in columns {
+            self.rendered.take();
+            self.order_by_columns.push(OrderBy {
+                column: x.to_string(),
+                is_asc,
+                nulls: None,
+                rank: None,
+            });
         }
         self
     }
 
-    pub fn group_by(mut self, columns: &[&str]) -> Self {
-        let len = columns.len();
-        if len == 0 {
+    /// full text search,skip if query is empty
+    /// mysql:    match (a,b) against (? in natural language mode)
+    /// pg:       to_tsvector(coalesce(a,'') || ' ' || coalesce(b,'')) @@ plainto_tsquery($1)
+    /// sqlite:   (a match ? or b match ?)  ,columns of the fts5 table,or the fts5 table name
+    /// mssql:    freetext((a,b), @p1)
+    /// for example:
+    ///  let w = rb.new_wrapper()
+    ///       .full_text(&["title", "content"], "rust orm", FullTextMode::Natural)
+    ///       .order_by_full_text(&["title", "content"], "rust orm", FullTextMode::Natural);
+    pub fn full_text(self, columns: &[&str], query: &str, mode: FullTextMode) -> Self {
+        if columns.is_empty() || query.trim().is_empty() {
             return self;
         }
-        let mut index = 0;
-        self.sql = self
-            .sql
-            .trim()
-            .trim_end_matches(crate::sql::TEMPLATE.r#where.left_space)
-            .trim_end_matches(crate::sql::TEMPLATE.and.left_space)
-            .trim_end_matches(crate::sql::TEMPLATE.or.left_space)
-            .to_string();
-        self.sql
-            .push_str(&crate::sql::TEMPLATE.group_by.left_right_space);
+        self.push_node(Node::FullText(FullText {
+            columns: columns.iter().map(|x| x.to_string()).collect(),
+            query: query.to_string(),
+            mode,
+        }))
+    }
+
+    fn json<T>(self, column: &str, op: JsonOp, path: &str, value: T) -> Self
+        where
+            T: Serialize,
+    {
+        self.push_node(Node::Json {
+            column: column.to_string(),
+            op,
+            path: path.to_string(),
+            value: json!(value),
+        })
+    }
+
+    /// value at the path of json column equal value,path like "a.b[0]"
+    /// mysql:    json_unquote(json_extract(column, ?)) = ?
+    /// pg:       column #>> $1::text[] = $2
+    /// sqlite:   json_extract(column, ?) = ?
+    pub fn json_eq<C, T>(self, column: C, path: &str, value: T) -> Self
+        where
+            C: IntoColumn,
+            T: Serialize,
+    {
+        self.json(column.column_name(), JsonOp::Eq, path, value)
+    }
+
+    /// json column contains the json value
+    /// mysql:    json_contains(column, ?)
+    /// pg:       column @> $1::jsonb
+    /// sqlite/mssql compare every leaf value of the json value
+    /// for example:
+    ///  json_contains("attrs", json!({"color": "red", "tags": ["new"]}))
+    pub fn json_contains<C, T>(self, column: C, value: T) -> Self
+        where
+            C: IntoColumn,
+            T: Serialize,
+    {
+        self.json(column.column_name(), JsonOp::Contains, "$", value)
+    }
+
+    /// key at the path of json column exists
+    /// mysql:    json_contains_path(column, 'one', ?)
+    /// pg:       column ? $1
+    /// sqlite:   json_type(column, ?) is not null
+    pub fn json_has_key<C>(self, column: C, path: &str) -> Self
+        where
+            C: IntoColumn,
+    {
+        self.json(column.column_name(), JsonOp::HasKey, path, Value::Null)
+    }
+
+    /// array at the path of json column contains the value,path "$" is the column itself
+    /// mysql:    json_contains(column, ?, ?)
+    /// pg:       column #> $1::text[] @> $2::jsonb
+    /// sqlite:   exists (select 1 from json_each(column, ?) where value = ?)
+    pub fn json_array_contains<C, T>(self, column: C, path: &str, value: T) -> Self
+        where
+            C: IntoColumn,
+            T: Serialize,
+    {
+        self.json(column.column_name(), JsonOp::ArrayContains, path, value)
+    }
+
+    /// order by full text relevance,most relevant first,skip if query is empty.
+    /// mysql/pg score with the same expression of full_text(),sqlite order by fts5 'rank',
+    /// mssql is not support(need CONTAINSTABLE) and will be ignored
+    pub fn order_by_full_text(mut self, columns: &[&str], query: &str, mode: FullTextMode) -> Self {
+        if columns.is_empty() || query.trim().is_empty() {
+            return self;
+        }
+        self.rendered.take();
+        self.order_by_columns.push(OrderBy {
+            column: String::new(),
+            is_asc: false,
+            nulls: None,
+            rank: Some(FullText {
+                columns: columns.iter().map(|x| x.to_string()).collect(),
+                query: query.to_string(),
+                mode,
+            }),
+        });
+        self
+    }
+
+    /// order by a sort spec from user input,every column must be in the whitelist
+    /// for example:
+    ///  //order by name asc,create_time desc nulls last
+    ///  let w = rb.new_wrapper().order_by_spec("name,-create_time nulls last", &["name", "create_time"])?;
+    pub fn order_by_spec(mut self, spec: &str, whitelist: &[&str]) -> Result<Self, SortError> {
+        let order_by = OrderBy::parse_spec(&self.driver_type, spec, whitelist)?;
+        self.rendered.take();
+        self.order_by_columns.extend(order_by);
+        Ok(self)
+    }
+
+    /// order by a sort spec from user input,every column must be in T::table_columns()
+    pub fn order_by_table_spec<T>(self, spec: &str) -> Result<Self, SortError>
+        where
+            T: CRUDTable,
+    {
+        let columns = T::table_columns();
+        let whitelist: Vec<&str> = columns.split(',').map(|x| x.trim()).collect();
+        self.order_by_spec(spec, &whitelist)
+    }
+
+    pub fn group_by(mut self, columns: &[&str]) -> Self {
         for x in columns {
-            self.sql.push_str(x);
-            if (index + 1) != len {
-                self.sql.push_str(",");
-                index += 1;
-            }
+            self.rendered.take();
+            self.group_by_columns.push(x.to_string());
         }
         self
     }
 
     ///  sql:   column > obj
-    pub fn gt<T>(mut self, column: &str, obj: T) -> Self
+    pub fn gt<C, T>(self, column: C, obj: T) -> Self
         where
+            C: ColumnArg<T>,
             T: Serialize,
     {
-        self = self.and();
-        let mut convert_column = String::new();
-        self.driver_type.stmt_convert(self.args.len(), &mut convert_column);
-        self.do_format_column(column, &mut convert_column);
-        push_sql!(self.sql,column," > ", &convert_column.as_str(),);
-        self.args.push(json!(obj));
-        self
+        self.compare(column.column_name(), CompareOp::Gt, obj)
     }
     ///  sql:   column >= obj
-    pub fn ge<T>(mut self, column: &str, obj: T) -> Self
+    pub fn ge<C, T>(self, column: C, obj: T) -> Self
         where
+            C: ColumnArg<T>,
             T: Serialize,
     {
-        self = self.and();
-        let mut convert_column = String::new();
-        self.driver_type.stmt_convert(self.args.len(), &mut convert_column);
-        self.do_format_column(column, &mut convert_column);
-        push_sql!(self.sql,column," >= ", &convert_column.as_str(),);
-        self.args.push(json!(obj));
-        self
+        self.compare(column.column_name(), CompareOp::Ge, obj)
     }
 
     ///  sql:   column < obj
-    pub fn lt<T>(mut self, column: &str, obj: T) -> Self
+    pub fn lt<C, T>(self, column: C, obj: T) -> Self
         where
+            C: ColumnArg<T>,
             T: Serialize,
     {
-        self = self.and();
-        let mut convert_column = String::new();
-        self.driver_type.stmt_convert(self.args.len(), &mut convert_column);
-        self.do_format_column(column, &mut convert_column);
-        push_sql!(self.sql,column," < ", &convert_column.as_str(),);
-        self.args.push(json!(obj));
-        self
+        self.compare(column.column_name(), CompareOp::Lt, obj)
     }
 
     ///  sql:   column <= obj
-    pub fn le<T>(mut self, column: &str, obj: T) -> Self
+    pub fn le<C, T>(self, column: C, obj: T) -> Self
         where
+            C: ColumnArg<T>,
             T: Serialize,
     {
-        self = self.and();
-        let mut convert_column = String::new();
-        self.driver_type.stmt_convert(self.args.len(), &mut convert_column);
-        self.do_format_column(column, &mut convert_column);
-        push_sql!(self.sql,column," <= ", &convert_column.as_str(),);
-        self.args.push(json!(obj));
-        self
+        self.compare(column.column_name(), CompareOp::Le, obj)
     }
 
-    pub fn between<T>(mut self, column: &str, min: T, max: T) -> Self
+    pub fn between<C, T>(self, column: C, min: T, max: T) -> Self
         where
+            C: ColumnArg<T>,
             T: Serialize,
     {
-        self = self.and();
-
-        let mut convert_column = String::new();
-        self.driver_type.stmt_convert(self.args.len(), &mut convert_column);
-        self.do_format_column(column, &mut convert_column);
-        push_sql!(self.sql,column," ",crate::sql::TEMPLATE.between.value," ", &convert_column.as_str(),);
-
-        let mut convert_column = String::new();
-        self.driver_type.stmt_convert(self.args.len(), &mut convert_column);
-        self.do_format_column(column, &mut convert_column);
-        push_sql!(self.sql," ",crate::sql::TEMPLATE.and.value," ", &convert_column.as_str(),);
-
-        self.args.push(json!(min));
-        self.args.push(json!(max));
-        self
+        self.push_node(Node::Between {
+            column: column.column_name().to_string(),
+            not: false,
+            min: json!(min),
+            max: json!(max),
+        })
     }
 
-    pub fn not_between<T>(mut self, column: &str, min: T, max: T) -> Self
+    pub fn not_between<C, T>(self, column: C, min: T, max: T) -> Self
         where
+            C: ColumnArg<T>,
             T: Serialize,
     {
-        self = self.and();
-
-        let mut convert_column = String::new();
-        self.driver_type.stmt_convert(self.args.len(), &mut convert_column);
-        self.do_format_column(column, &mut convert_column);
-        push_sql!(self.sql,column," ",crate::sql::TEMPLATE.not.value,crate::sql::TEMPLATE.between.value," ", &convert_column.as_str(),);
-
-        let mut convert_column = String::new();
-        self.driver_type.stmt_convert(self.args.len(), &mut convert_column);
-        self.do_format_column(column, &mut convert_column);
-        push_sql!(self.sql," ",crate::sql::TEMPLATE.and.value," ", &convert_column.as_str(),);
-
-        self.args.push(json!(min));
-        self.args.push(json!(max));
-        self
+        self.push_node(Node::Between {
+            column: column.column_name().to_string(),
+            not: true,
+            min: json!(min),
+            max: json!(max),
+        })
     }
 
-    pub fn like<T>(mut self, column: &str, obj: T) -> Self
+    fn like_node<T>(self, column: &str, not: bool, obj: T, pattern: fn(&str) -> String, escape: bool, ignore_case: bool) -> Self
         where
             T: Serialize,
     {
-        self = self.and();
         let v = json!(obj);
-        let mut v_str = String::new();
-        if v.is_string() {
-            v_str = format!("%{}%", v.as_str().unwrap());
+        let v_str = match v.as_str() {
+            Some(s) => s.to_string(),
+            None => v.to_string(),
+        };
+        let arg = if escape {
+            pattern(&escape_like(&v_str))
         } else {
-            v_str = format!("%{}%", v.to_string());
-        }
-
-        let mut convert_column = String::new();
-        self.driver_type.stmt_convert(self.args.len(), &mut convert_column);
-        self.do_format_column(column, &mut convert_column);
-        push_sql!(self.sql,column," ",crate::sql::TEMPLATE.like.value," ", &convert_column.as_str(),);
+            v_str
+        };
+        self.push_node(Node::Like {
+            column: column.to_string(),
+            not,
+            arg: json!(arg),
+            escape,
+            ignore_case,
+        })
+    }
 
-        self.args.push(json!(v_str));
-        self
+    /// sql: column like '%obj%',the '%','_' of obj will be escaped
+    /// for example:
+    ///  like("name", "50%")   "name like ? escape '!'"  arg:"%50!%%"
+    pub fn like<C, T>(self, column: C, obj: T) -> Self
+        where
+            C: ColumnArg<T>,
+            T: Serialize,
+    {
+        self.like_node(column.column_name(), false, obj, |v| format!("%{}%", v), true, false)
     }
 
-    pub fn like_left<T>(mut self, column: &str, obj: T) -> Self
+    /// sql: column like '%obj',the '%','_' of obj will be escaped
+    pub fn like_left<C, T>(self, column: C, obj: T) -> Self
         where
+            C: ColumnArg<T>,
             T: Serialize,
     {
-        self = self.and();
-        let v = json!(obj);
-        let mut v_str = String::new();
-        if v.is_string() {
-            v_str = format!("%{}", v.as_str().unwrap());
-        } else {
-            v_str = format!("%{}", v.to_string());
-        }
+        self.like_node(column.column_name(), false, obj, |v| format!("%{}", v), true, false)
+    }
 
-        let mut convert_column = String::new();
-        self.driver_type.stmt_convert(self.args.len(), &mut convert_column);
-        self.do_format_column(column, &mut convert_column);
-        push_sql!(self.sql,column," ",crate::sql::TEMPLATE.like.value," ", &convert_column.as_str(),);
+    /// sql: column like 'obj%',the '%','_' of obj will be escaped
+    pub fn like_right<C, T>(self, column: C, obj: T) -> Self
+        where
+            C: ColumnArg<T>,
+            T: Serialize,
+    {
+        self.like_node(column.column_name(), false, obj, |v| format!("{}%", v), true, false)
+    }
 
-        self.args.push(json!(v_str));
-        self
+    /// sql: column not like '%obj%',the '%','_' of obj will be escaped
+    pub fn not_like<C, T>(self, column: C, obj: T) -> Self
+        where
+            C: ColumnArg<T>,
+            T: Serialize,
+    {
+        self.like_node(column.column_name(), true, obj, |v| format!("%{}%", v), true, false)
     }
 
-    pub fn like_right<T>(mut self, column: &str, obj: T) -> Self
+    /// sql: column like pattern,the wildcards of pattern will be passed through
+    /// for example:
+    ///  like_raw("name", "a_b%")
+    pub fn like_raw<C, T>(self, column: C, pattern: T) -> Self
         where
+            C: ColumnArg<T>,
             T: Serialize,
     {
-        self = self.and();
-        let v = json!(obj);
-        let mut v_str = String::new();
-        if v.is_string() {
-            v_str = format!("{}%", v.as_str().unwrap());
-        } else {
-            v_str = format!("{}%", v.to_string());
-        }
+        self.like_node(column.column_name(), false, pattern, |v| v.to_string(), false, false)
+    }
 
-        let mut convert_column = String::new();
-        self.driver_type.stmt_convert(self.args.len(), &mut convert_column);
-        self.do_format_column(column, &mut convert_column);
-        push_sql!(self.sql,column," ",crate::sql::TEMPLATE.like.value," ", &convert_column.as_str(),);
+    /// sql: column not like pattern,the wildcards of pattern will be passed through
+    pub fn not_like_raw<C, T>(self, column: C, pattern: T) -> Self
+        where
+            C: ColumnArg<T>,
+            T: Serialize,
+    {
+        self.like_node(column.column_name(), true, pattern, |v| v.to_string(), false, false)
+    }
 
-        self.args.push(json!(v_str));
-        self
+    /// case insensitive like '%obj%',the '%','_' of obj will be escaped
+    /// pg:     column ilike $1
+    /// other:  lower(column) like lower(?)
+    pub fn ilike<C, T>(self, column: C, obj: T) -> Self
+        where
+            C: ColumnArg<T>,
+            T: Serialize,
+    {
+        self.like_node(column.column_name(), false, obj, |v| format!("%{}%", v), true, true)
     }
 
-    pub fn not_like<T>(mut self, column: &str, obj: T) -> Self
+    /// case insensitive not like '%obj%',the '%','_' of obj will be escaped
+    pub fn not_ilike<C, T>(self, column: C, obj: T) -> Self
         where
+            C: ColumnArg<T>,
             T: Serialize,
     {
-        self = self.and();
-        let v = json!(obj);
-        let mut v_str = String::new();
-        if v.is_string() {
-            v_str = format!("%{}%", v.as_str().unwrap());
-        } else {
-            v_str = format!("%{}%", v.to_string());
-        }
-        let mut convert_column = String::new();
-        self.driver_type.stmt_convert(self.args.len(), &mut convert_column);
-        self.do_format_column(column, &mut convert_column);
-        push_sql!(self.sql,column," ",crate::sql::TEMPLATE.not.value," ",crate::sql::TEMPLATE.like.value," ", &convert_column.as_str(),);
-        self.args.push(json!(v_str));
-        self
+        self.like_node(column.column_name(), true, obj, |v| format!("%{}%", v), true, true)
     }
 
-    pub fn is_null(mut self, column: &str) -> Self {
-        self = self.and();
-        self.sql.push_str(column);
-        self.sql.push_str(crate::sql::TEMPLATE.is.left_right_space);
-        self.sql.push_str(crate::sql::TEMPLATE.null.right_space);
-        self
+    pub fn is_null<C>(self, column: C) -> Self
+        where
+            C: IntoColumn,
+    {
+        self.push_node(Node::IsNull {
+            column: column.column_name().to_string(),
+            not: false,
+        })
     }
 
-    pub fn is_not_null(mut self, column: &str) -> Self {
-        self = self.and();
-        self.sql.push_str(column);
-        self.sql.push_str(crate::sql::TEMPLATE.is.left_right_space);
-        self.sql.push_str(crate::sql::TEMPLATE.not.right_space);
-        self.sql.push_str(crate::sql::TEMPLATE.null.right_space);
-        self
+    pub fn is_not_null<C>(self, column: C) -> Self
+        where
+            C: IntoColumn,
+    {
+        self.push_node(Node::IsNull {
+            column: column.column_name().to_string(),
+            not: true,
+        })
     }
 
     /// gen sql: * in (*,*,*)
-    pub fn in_array<T>(mut self, column: &str, obj: &[T]) -> Self
+    /// empty obj will be '1 = 0',pg list(len >= 100) of same type will be '* = any($1)' with an array arg.
+    /// the list over the param limit of driver is inlined if all values are integer,or split to '(* in (..) or * in (..))'
+    pub fn in_array<C, T>(self, column: C, obj: &[T]) -> Self
         where
+            C: ColumnArg<T>,
             T: Serialize,
     {
-        if obj.len() == 0 {
-            return self;
-        }
-        self = self.and();
-        push_sql!(self.sql,column," ",crate::sql::TEMPLATE.r#in.value," (",);
-        for x in obj {
-            let mut convert_column = String::new();
-            self.driver_type.stmt_convert(self.args.len(), &mut convert_column);
-            self.do_format_column(column, &mut convert_column);
-            push_sql!(self.sql," ",&convert_column.as_str()," ",);
-            self.sql.push_str(",");
-            self.args.push(json!(x));
-        }
-        self.sql.pop();
-        push_sql!(self.sql,")",);
-        self
+        self.push_node(Node::In {
+            column: column.column_name().to_string(),
+            not: false,
+            args: obj.iter().map(|x| json!(x)).collect(),
+        })
     }
 
     /// gen sql: * in (*,*,*)
-    pub fn in_<T>(self, column: &str, obj: &[T]) -> Self
+    pub fn in_<C, T>(self, column: C, obj: &[T]) -> Self
         where
+            C: ColumnArg<T>,
             T: Serialize,
     {
         self.in_array(column, obj)
     }
 
     /// gen sql: * in (*,*,*)
-    pub fn r#in<T>(self, column: &str, obj: &[T]) -> Self
+    pub fn r#in<C, T>(self, column: C, obj: &[T]) -> Self
         where
+            C: ColumnArg<T>,
             T: Serialize,
     {
         self.in_array(column, obj)
     }
 
-    pub fn not_in<T>(mut self, column: &str, obj: &[T]) -> Self
+    /// gen sql: * not in (*,*,*)
+    /// empty obj will be '1 = 1',pg list(len >= 100) of same type will be '* <> all($1)' with an array arg.
+    /// the list over the param limit of driver is inlined if all values are integer,or split to '(* not in (..) and * not in (..))'
+    pub fn not_in<C, T>(self, column: C, obj: &[T]) -> Self
         where
+            C: ColumnArg<T>,
             T: Serialize,
     {
-        if obj.len() == 0 {
-            return self;
+        self.push_node(Node::In {
+            column: column.column_name().to_string(),
+            not: true,
+            args: obj.iter().map(|x| json!(x)).collect(),
+        })
+    }
+
+    /// select columns from table T,make this wrapper a full select sql for in_sub(),exists()...
+    /// columns is empty will select T::table_columns()
+    /// for example:
+    ///  rb.new_wrapper_table::<User>().select::<User>(&["id"]).eq("status", 1)  // select id from user where status = ?
+    pub fn select<T>(mut self, columns: &[&str]) -> Self
+        where
+            T: CRUDTable,
+    {
+        let mut table_name = T::table_name();
+        if let Some(f) = self.formats.get("table_name") {
+            table_name = f(&table_name);
         }
-        self = self.and();
-        push_sql!(self.sql,column," ",crate::sql::TEMPLATE.not.value," ",crate::sql::TEMPLATE.r#in.value," (",);
-        for x in obj {
-            let mut convert_column = String::new();
-            self.driver_type.stmt_convert(self.args.len(), &mut convert_column);
-            self.do_format_column(column, &mut convert_column);
-            push_sql!(self.sql," ",&convert_column.as_str()," ",);
-            self.sql.push_str(",");
-            self.args.push(json!(x));
-        }
-        self.sql.pop();
-        push_sql!(self.sql,")",);
+        let columns = if columns.is_empty() {
+            T::table_columns()
+        } else {
+            columns.join(",")
+        };
+        self.rendered.take();
+        self.select = Some(Select {
+            table_name,
+            columns,
+        });
         self
     }
 
-    pub fn trim_space(mut self) -> Self {
-        self.sql = self.sql.replace("  ", " ");
-        return self;
+    /// gen sql: column in (select ...),placeholders of sub will be renumbered
+    /// the sub wrapper must call select::<T>() first,or return error
+    /// for example:
+    ///  w.in_sub("id", rb.new_wrapper_table::<User>().select::<User>(&["activity_id"]).eq("status", 1))?
+    ///  // id in (select activity_id from user where status = $1)
+    pub fn in_sub<C>(self, column: C, sub: Wrapper) -> Result<Self, Error>
+        where
+            C: IntoColumn,
+    {
+        self.push_sub(Some(column.column_name().to_string()), false, sub)
     }
 
-    pub fn trim_and(mut self) -> Self {
-        self.sql = self
-            .sql
-            .trim()
-            .trim_start_matches(crate::sql::TEMPLATE.and.right_space)
-            .trim_end_matches(crate::sql::TEMPLATE.and.left_space)
-            .to_string();
+    /// gen sql: column not in (select ...)
+    pub fn not_in_sub<C>(self, column: C, sub: Wrapper) -> Result<Self, Error>
+        where
+            C: IntoColumn,
+    {
+        self.push_sub(Some(column.column_name().to_string()), true, sub)
+    }
+
+    /// gen sql: exists (select ...)
+    pub fn exists(self, sub: Wrapper) -> Result<Self, Error> {
+        self.push_sub(None, false, sub)
+    }
+
+    /// gen sql: not exists (select ...)
+    pub fn not_exists(self, sub: Wrapper) -> Result<Self, Error> {
+        self.push_sub(None, true, sub)
+    }
+
+    fn push_sub(self, column: Option<String>, not: bool, sub: Wrapper) -> Result<Self, Error> {
+        if sub.select.is_none() {
+            return Err(Error::from("[rbatis] sub wrapper must call select::<T>() first!"));
+        }
+        Ok(self.push_node(Node::Sub {
+            column,
+            not,
+            sub: Box::new(sub),
+        }))
+    }
+
+    pub fn trim_space(self) -> Self {
+        self.trim_value("  ", " ")
+    }
+
+    /// rendered sql never start or end with 'and',keep for compatibility
+    pub fn trim_and(self) -> Self {
         self
     }
 
-    pub fn trim_or(mut self) -> Self {
-        self.sql = self
-            .sql
-            .trim()
-            .trim_start_matches(crate::sql::TEMPLATE.or.right_space)
-            .trim_end_matches(crate::sql::TEMPLATE.or.left_space)
-            .to_owned();
+    /// rendered sql never start or end with 'or',keep for compatibility
+    pub fn trim_or(self) -> Self {
         self
     }
 
-    pub fn trim_and_or(mut self) -> Self {
-        self.sql = self
-            .sql
-            .trim()
-            .trim_start_matches(crate::sql::TEMPLATE.and.right_space)
-            .trim_end_matches(crate::sql::TEMPLATE.and.left_space)
-            .trim_start_matches(crate::sql::TEMPLATE.and.right_space)
-            .trim_end_matches(crate::sql::TEMPLATE.and.left_space)
-            .to_owned();
+    /// rendered sql never start or end with 'and'/'or',keep for compatibility
+    pub fn trim_and_or(self) -> Self {
         self
     }
 
-    pub fn insert_into(mut self, table_name: &str, columns: &str, values: &str) -> Self {
+    /// replace all sql with the insert sql,args will be keep
+    pub fn insert_into(self, table_name: &str, columns: &str, values: &str) -> Self {
+        let sql;
         if values.starts_with("(") && values.ends_with(")") {
-            self.sql = format!(
+            sql = format!(
                 "{} {} ({}) {} ({})",
                 crate::sql::TEMPLATE.insert_into.value,
                 table_name,
@@ -716,7 +1851,7 @@ impl Wrapper {
                 values
             );
         } else {
-            self.sql = format!(
+            sql = format!(
                 "{} {} ({}) {} {}",
                 crate::sql::TEMPLATE.insert_into.value,
                 table_name,
@@ -725,17 +1860,340 @@ impl Wrapper {
                 values
             );
         }
-        self
+        self.set_sql(&sql)
     }
 
     /// limit
     /// for example:
     ///  limit(1) " limit 1 "
     pub fn limit(mut self, limit: u64) -> Self {
-        use std::fmt::Write;
-        push_sql!(self.sql," ",crate::sql::TEMPLATE.limit.value," ",);
-        self.sql.write_fmt(format_args!("{}", limit));
-        self.sql.push_str(" ");
+        self.rendered.take();
+        self.limit_size = Some(limit);
         self
     }
 }
+
+/// build Wrapper conditions from a query struct,impl by #[derive(WrapperFilter)]
+/// for example:
+///  #[derive(WrapperFilter)]
+///  pub struct ActivityQuery {
+///     #[filter(op = "like")]
+///     pub name: Option<String>,
+///     #[filter(op = "ge", column = "create_time")]
+///     pub create_time_start: Option<NaiveDateTime>,
+///     #[filter(op = "in", column = "status")]
+///     pub status_in: Option<Vec<i32>>,
+///     #[filter(skip)]
+///     pub page_no: u64,
+///  }
+///  //name like ? and create_time >= ? and status in ( ? , ? )
+///  let w = rb.new_wrapper().filter(&query);
+pub trait WrapperFilter {
+    fn filter(&self, w: Wrapper) -> Wrapper;
+}
+
+/// pg in list with len >= this will be rendered as '= any($1)'
+const ANY_ARRAY_MIN_LEN: usize = 100;
+
+/// max values of one 'in' list,less than the param limit of driver for other args.
+/// CRUD split the longer list to sqls of this size,and Wrapper inline the longer list if all values are integer
+pub(crate) fn max_in_len(driver_type: &DriverType) -> usize {
+    match driver_type {
+        DriverType::Mysql => 65000,
+        DriverType::Postgres => 32000,
+        DriverType::Sqlite => 900,
+        DriverType::Mssql => 2000,
+        _ => 900,
+    }
+}
+
+/// max params of one sql
+pub fn max_params(driver_type: &DriverType) -> usize {
+    match driver_type {
+        DriverType::Mysql => 65535,
+        DriverType::Postgres => 32767,
+        DriverType::Sqlite => 999,
+        DriverType::Mssql => 2100,
+        _ => 999,
+    }
+}
+
+/// error of too many params in one sql,see check_params()
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TooManyParams {
+    pub driver_type: DriverType,
+    pub len: usize,
+    pub max: usize,
+}
+
+impl std::fmt::Display for TooManyParams {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[rbatis] sql have {} params,over the limit {} of {:?},please split it(for example: CRUD::fetch_list_by_column())!", self.len, self.max, self.driver_type)
+    }
+}
+
+impl std::error::Error for TooManyParams {}
+
+impl From<TooManyParams> for Error {
+    fn from(e: TooManyParams) -> Self {
+        Error::from(e.to_string())
+    }
+}
+
+/// check the params of one sql not over the limit of driver
+pub fn check_params(driver_type: &DriverType, args: &[Value]) -> Result<(), TooManyParams> {
+    let max = max_params(driver_type);
+    if args.len() > max {
+        return Err(TooManyParams {
+            driver_type: driver_type.clone(),
+            len: args.len(),
+            max,
+        });
+    }
+    Ok(())
+}
+
+/// all items are string,integer,float or bool
+fn is_same_type_array(args: &[Value]) -> bool {
+    let first = &args[0];
+    args.iter().all(|x| match (first, x) {
+        (Value::String(_), Value::String(_)) => true,
+        (Value::Bool(_), Value::Bool(_)) => true,
+        (Value::Number(a), Value::Number(b)) => a.is_f64() == b.is_f64(),
+        _ => false,
+    })
+}
+
+/// escape the '!','%','_' of like arg with '!','[' of mssql is escaped when render
+fn escape_like(arg: &str) -> String {
+    let mut result = String::with_capacity(arg.len() + 2);
+    for c in arg.chars() {
+        if c == '!' || c == '%' || c == '_' {
+            result.push('!');
+        }
+        result.push(c);
+    }
+    result
+}
+
+/// normalize json path to "$.a.b[0]"
+fn json_path(path: &str) -> String {
+    let path = path.trim();
+    if path.is_empty() {
+        "$".to_string()
+    } else if path.starts_with('$') {
+        path.to_string()
+    } else if path.starts_with('[') {
+        format!("${}", path)
+    } else {
+        format!("$.{}", path)
+    }
+}
+
+/// "$.a.b[0]" to pg text array "{a,b,0}"
+fn pg_json_path(json_path: &str) -> String {
+    let keys: Vec<&str> = json_path.trim_start_matches('$')
+        .split(|c| c == '.' || c == '[' || c == ']')
+        .filter(|x| !x.is_empty())
+        .collect();
+    format!("{{{}}}", keys.join(","))
+}
+
+/// json value as sql text,string without quotes
+fn json_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        _ => value.to_string(),
+    }
+}
+
+/// (op,path,value) of every leaf of the json value,array items will be ArrayContains
+fn json_leaves(path: &str, value: &Value, items: &mut Vec<(JsonOp, String, Value)>) {
+    match value {
+        Value::Object(m) => {
+            for (k, v) in m {
+                json_leaves(&format!("{}.{}", path, k), v, items);
+            }
+        }
+        Value::Array(arr) => {
+            for v in arr {
+                items.push((JsonOp::ArrayContains, path.to_string(), v.clone()));
+            }
+        }
+        _ => {
+            items.push((JsonOp::Eq, path.to_string(), value.clone()));
+        }
+    }
+}
+
+/// push arg,return the placeholder of it
+fn push_arg_placeholder(driver_type: &DriverType, arg: Value, args: &mut Vec<Value>) -> String {
+    let mut placeholder = String::new();
+    driver_type.stmt_convert(args.len(), &mut placeholder);
+    args.push(arg);
+    placeholder
+}
+
+/// the driver support 'order by column nulls first/last'
+fn support_nulls_order(driver_type: &DriverType) -> bool {
+    match driver_type {
+        DriverType::Postgres | DriverType::Sqlite => true,
+        _ => false,
+    }
+}
+
+/// can not add 'and'/'or' if sql end with where,and,or,'(' or operator
+fn not_allow_link(sql: &str) -> bool {
+    let sql = sql.trim_end();
+    if sql.is_empty() {
+        return true;
+    }
+    sql.ends_with(crate::sql::TEMPLATE.r#where.left_space)
+        || sql.ends_with(crate::sql::TEMPLATE.and.left_space)
+        || sql.ends_with(crate::sql::TEMPLATE.or.left_space)
+        || sql.ends_with("(")
+        || sql.ends_with(",")
+        || sql.ends_with("=")
+        || sql.ends_with("+")
+        || sql.ends_with("-")
+        || sql.ends_with("*")
+        || sql.ends_with("/")
+        || sql.ends_with("%")
+        || sql.ends_with("^")
+        || sql.ends_with(">")
+        || sql.ends_with("<")
+        || sql.ends_with("&")
+        || sql.ends_with("|")
+}
+
+fn push_link(sql: &mut String, link: &Node) {
+    if not_allow_link(sql) {
+        return;
+    }
+    match link {
+        Node::Or => sql.push_str(crate::sql::TEMPLATE.or.left_right_space),
+        _ => sql.push_str(crate::sql::TEMPLATE.and.left_right_space),
+    }
+}
+
+/// is nodes render no sql
+fn is_empty_nodes(nodes: &[Node]) -> bool {
+    nodes.iter().all(|x| match x {
+        Node::And | Node::Or | Node::Arg(_) => true,
+        Node::Group(nodes) => is_empty_nodes(nodes),
+        Node::Raw { sql, .. } | Node::Sql(sql) => sql.trim().is_empty(),
+        _ => false,
+    })
+}
+
+/// trim 'and'/'or' of start and end
+fn trim_link(sql: &str) -> &str {
+    let mut sql = sql.trim();
+    loop {
+        let trimmed = sql
+            .trim_start_matches(crate::sql::TEMPLATE.and.right_space)
+            .trim_start_matches(crate::sql::TEMPLATE.or.right_space)
+            .trim_end_matches(crate::sql::TEMPLATE.and.left_space)
+            .trim_end_matches(crate::sql::TEMPLATE.or.left_space)
+            .trim();
+        if trimmed.len() == sql.len() {
+            return sql;
+        }
+        sql = trimmed;
+    }
+}
+
+/// trim 'and'/'or' of start and end, and 'where' of end
+fn trim_where(sql: &str) -> &str {
+    let mut sql = sql.trim();
+    loop {
+        let mut trimmed = trim_link(sql);
+        if trimmed == crate::sql::TEMPLATE.r#where.value {
+            trimmed = "";
+        }
+        let trimmed = trimmed.trim_end_matches(crate::sql::TEMPLATE.r#where.left_space);
+        if trimmed.len() == sql.len() {
+            return sql;
+        }
+        sql = trimmed;
+    }
+}
+
+/// convert placeholders of the sql(write for driver 'from',number from 1) to driver 'to',start with index offset.
+/// placeholders in quotes will be skip
+fn convert_placeholders(sql: &str, from: &DriverType, to: &DriverType, offset: usize) -> String {
+    let mut result = String::with_capacity(sql.len());
+    let chars: Vec<char> = sql.chars().collect();
+    let mut quote: Option<char> = None;
+    let mut question_index = 0;
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if let Some(q) = quote {
+            if c == q {
+                quote = None;
+            }
+            result.push(c);
+            i += 1;
+            continue;
+        }
+        if c == '\'' || c == '"' || c == '`' {
+            quote = Some(c);
+            result.push(c);
+            i += 1;
+            continue;
+        }
+        //(placeholder index,placeholder len)
+        let placeholder = match from {
+            DriverType::Postgres => {
+                if c == '$' {
+                    parse_placeholder_index(&chars, i + 1).map(|(index, len)| (index, len + 1))
+                } else {
+                    None
+                }
+            }
+            DriverType::Mssql => {
+                if c == '@' && chars.get(i + 1) == Some(&'p') {
+                    parse_placeholder_index(&chars, i + 2).map(|(index, len)| (index, len + 2))
+                } else {
+                    None
+                }
+            }
+            _ => {
+                if c == '?' {
+                    question_index += 1;
+                    Some((question_index - 1, 1))
+                } else {
+                    None
+                }
+            }
+        };
+        match placeholder {
+            Some((index, len)) => {
+                let mut convert_column = String::new();
+                to.stmt_convert(offset + index, &mut convert_column);
+                result.push_str(&convert_column);
+                i += len;
+            }
+            None => {
+                result.push(c);
+                i += 1;
+            }
+        }
+    }
+    result
+}
+
+/// parse the number start at chars[start],return (number - 1,digits len)
+fn parse_placeholder_index(chars: &[char], start: usize) -> Option<(usize, usize)> {
+    let mut len = 0;
+    let mut num: usize = 0;
+    while let Some(d) = chars.get(start + len).and_then(|c| c.to_digit(10)) {
+        num = num * 10 + d as usize;
+        len += 1;
+    }
+    if len == 0 || num == 0 {
+        return None;
+    }
+    Some((num - 1, len))
+}
@@ -0,0 +1,120 @@
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use rbatis::core::db::DriverType;
+    use rbatis::plugin::audit::{diff, make_audit_sql, make_delete_records, make_insert_records, make_update_records, mask_records, AuditOperation, RbatisAuditPlugin};
+    use rbatis::crud::CRUDTable;
+    use rbatis::plugin::logic_delete::{RbatisLogicDeletePlugin, TableNoLogic};
+    use rbatis::plugin::version_lock::{RbatisVersionLockPlugin, TableNoVersion};
+    use rbatis::rbatis::Rbatis;
+
+    #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+    pub struct User {
+        pub id: Option<i64>,
+    }
+
+    impl CRUDTable for User {
+        fn is_use_plugin(plugin_name: &str) -> bool {
+            plugin_name != std::any::type_name::<RbatisAuditPlugin>()
+        }
+
+        fn table_name() -> String {
+            "user".to_string()
+        }
+
+        fn table_columns() -> String {
+            "id".to_string()
+        }
+    }
+
+    #[test]
+    fn test_diff() {
+        let old = json!({"id": 1, "name": "a", "version": 1, "remark": null});
+        let new = json!({"name": "b", "version": 2, "remark": null});
+        let (old, new) = diff(old.as_object().unwrap(), new.as_object().unwrap()).unwrap();
+        assert_eq!(old, json!({"name": "a", "version": 1}));
+        assert_eq!(new, json!({"name": "b", "version": 2}));
+
+        let old = json!({"id": 1, "name": "a"});
+        let new = json!({"name": "a"});
+        assert_eq!(diff(old.as_object().unwrap(), new.as_object().unwrap()), None);
+    }
+
+    #[test]
+    fn test_records() {
+        let plugin = RbatisAuditPlugin::table("sys_audit").actor(|| Some("admin".to_string()));
+        let records = make_insert_records(&plugin, "biz_activity", vec![json!({"id": null, "name": "a"})], Some(12));
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].operation, AuditOperation::Insert);
+        assert_eq!(records[0].primary_key, json!(12));
+        assert_eq!(records[0].actor, Some("admin".to_string()));
+        assert_eq!(records[0].old, None);
+
+        //batch insert can not know the id of each row
+        let records = make_insert_records(&plugin, "biz_activity", vec![json!({"name": "a"}), json!({"id": "2", "name": "b"})], Some(12));
+        assert_eq!(records[0].primary_key, json!(null));
+        assert_eq!(records[1].primary_key, json!("2"));
+
+        let sets = json!({"name": "c", "version": 2});
+        let olds = vec![json!({"id": "1", "name": "a", "version": 1}), json!({"id": "2", "name": "c", "version": 2})];
+        let records = make_update_records(&plugin, "biz_activity", olds, sets.as_object().unwrap());
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].primary_key, json!("1"));
+        assert_eq!(records[0].old, Some(json!({"name": "a", "version": 1})));
+        assert_eq!(records[0].new, Some(json!({"name": "c", "version": 2})));
+
+        let plugin = RbatisAuditPlugin::table("sys_audit").primary_key("code");
+        let records = make_delete_records(&plugin, "biz_activity", vec![json!({"code": "x", "name": "a"})]);
+        assert_eq!(records[0].operation, AuditOperation::Delete);
+        assert_eq!(records[0].primary_key, json!("x"));
+        assert_eq!(records[0].new, None);
+        assert_eq!(records[0].actor, None);
+    }
+
+    #[test]
+    fn test_mask_records() {
+        let plugin = RbatisAuditPlugin::table("sys_audit");
+        let sets = json!({"name": "b", "password": "new"});
+        let olds = vec![json!({"id": 1, "name": "a", "password": "old"})];
+        let mut records = make_update_records(&plugin, "user", olds, sets.as_object().unwrap());
+        records.extend(make_insert_records(&plugin, "user", vec![json!({"id": 2, "name": "c", "password": null})], None));
+        mask_records(&mut records, &["password".to_string()]);
+        assert_eq!(records[0].old, Some(json!({"name": "a", "password": "******"})));
+        assert_eq!(records[0].new, Some(json!({"name": "b", "password": "******"})));
+        assert_eq!(records[1].new, Some(json!({"id": 2, "name": "c", "password": null})));
+    }
+
+    #[test]
+    fn test_table_plugin() {
+        //TableNoVersion/TableNoLogic only disable their plugin
+        assert!(!TableNoVersion::<User>::is_use_plugin(std::any::type_name::<RbatisVersionLockPlugin>()));
+        assert!(TableNoVersion::<User>::is_use_plugin(std::any::type_name::<RbatisLogicDeletePlugin>()));
+        assert!(!TableNoVersion::<User>::is_use_plugin(std::any::type_name::<RbatisAuditPlugin>()));
+        assert!(!TableNoLogic::<User>::is_use_plugin(std::any::type_name::<RbatisAuditPlugin>()));
+        assert!(!TableNoLogic::<User>::is_use_plugin(std::any::type_name::<RbatisLogicDeletePlugin>()));
+        assert!(TableNoLogic::<User>::is_use_plugin(std::any::type_name::<RbatisVersionLockPlugin>()));
+    }
+
+    #[test]
+    fn test_audit_sql() {
+        let plugin = RbatisAuditPlugin::table("sys_audit");
+        let records = make_delete_records(&plugin, "biz_activity", vec![json!({"id": 1}), json!({"id": 2})]);
+        let (sql, args) = make_audit_sql(&DriverType::Postgres, "sys_audit", &records);
+        assert_eq!(sql, "insert into sys_audit (table_name,primary_key,operation,old_value,new_value,actor,create_time) values ($1,$2,$3,$4,$5,$6,$7),($8,$9,$10,$11,$12,$13,$14)");
+        assert_eq!(args.len(), 14);
+        assert_eq!(args[0], json!("biz_activity"));
+        assert_eq!(args[1], json!("1"));
+        assert_eq!(args[2], json!("delete"));
+        assert_eq!(args[3], json!("{\"id\":1}"));
+        assert_eq!(args[4], json!(null));
+    }
+
+    #[test]
+    fn test_set_plugin() {
+        let mut rb = Rbatis::new();
+        assert!(rb.audit_plugin().is_none());
+        rb.set_audit_plugin(Some(RbatisAuditPlugin::table("sys_audit")));
+        assert_eq!(rb.audit_plugin().unwrap().audit_table(), Some("sys_audit"));
+    }
+}
@@ -0,0 +1,6 @@
+/// typed columns of #[crud_table] must be checked at compile time
+#[test]
+fn test_compile_fail() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/*.rs");
+}
@@ -0,0 +1,162 @@
+#[cfg(test)]
+mod test {
+    use futures::executor::block_on;
+    use serde::de::DeserializeOwned;
+    use serde_json::Value;
+
+    use rbatis::core::db::{DBExecResult, DriverType};
+    use rbatis::core::Error;
+    use rbatis::crud::{CRUDMut, CRUDTable};
+    use rbatis::crud_table;
+    use rbatis::executor::{ExecutorMut, RbatisRef};
+    use rbatis::plugin::logic_delete::TableNoLogic;
+    use rbatis::plugin::version_lock::TableNoVersion;
+    use rbatis::rbatis::Rbatis;
+    use rbatis::wrapper::Wrapper;
+
+    /// record the sql,only impl exec()/fetch()
+    pub struct MockExecutor {
+        pub rb: Rbatis,
+        pub sqls: Vec<String>,
+    }
+
+    impl RbatisRef for MockExecutor {
+        fn get_rbatis(&self) -> &Rbatis {
+            &self.rb
+        }
+
+        fn driver_type(&self) -> rbatis::Result<DriverType> {
+            Ok(DriverType::Mysql)
+        }
+    }
+
+    #[rbatis::async_trait]
+    impl ExecutorMut for MockExecutor {
+        async fn exec(&mut self, sql: &str, _args: &Vec<Value>) -> Result<DBExecResult, Error> {
+            self.sqls.push(sql.to_string());
+            Ok(DBExecResult {
+                rows_affected: 1,
+                last_insert_id: None,
+            })
+        }
+
+        async fn fetch<T>(&mut self, sql: &str, _args: &Vec<Value>) -> Result<T, Error> where T: DeserializeOwned {
+            self.sqls.push(sql.to_string());
+            serde_json::from_value(Value::Array(vec![])).map_err(|e| Error::from(e.to_string()))
+        }
+    }
+
+    impl CRUDMut for MockExecutor {}
+
+    #[crud_table(table_name:"biz_hook"|before_insert:"validate"|after_fetch:"decode"|before_delete:"check_delete")]
+    #[derive(Clone, Debug)]
+    pub struct BizHook {
+        pub id: Option<String>,
+        pub name: Option<String>,
+    }
+
+    impl BizHook {
+        async fn validate<E>(&self, executor: &mut E) -> rbatis::Result<Option<Self>>
+            where E: ExecutorMut + Send + ?Sized {
+            match &self.name {
+                None => Err(Error::from("name can not be empty!")),
+                Some(name) => {
+                    executor.exec_with(Some("biz_hook"), "select 'validate'", &vec![]).await?;
+                    Ok(Some(BizHook { id: self.id.clone(), name: Some(name.trim().to_string()) }))
+                }
+            }
+        }
+
+        async fn decode<E>(&mut self, _executor: &mut E) -> rbatis::Result<()>
+            where E: ExecutorMut + Send + ?Sized {
+            self.name = self.name.as_ref().map(|x| x.to_uppercase());
+            Ok(())
+        }
+
+        async fn check_delete<E>(_executor: &mut E, w: &Wrapper) -> rbatis::Result<()>
+            where E: ExecutorMut + Send + ?Sized {
+            if w.is_empty() {
+                return Err(Error::from("can not delete all!"));
+            }
+            Ok(())
+        }
+    }
+
+    /// the hooks of a manual impl are called too
+    #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+    pub struct BizManual {
+        pub id: Option<String>,
+        pub name: Option<String>,
+    }
+
+    #[rbatis::async_trait]
+    impl CRUDTable for BizManual {
+        fn table_name() -> String {
+            "biz_manual".to_string()
+        }
+
+        fn table_columns() -> String {
+            "id,name".to_string()
+        }
+
+        async fn before_insert<E>(&self, _executor: &mut E) -> rbatis::Result<Option<Self>>
+            where E: ExecutorMut + Send + ?Sized {
+            Ok(Some(BizManual { id: Some("1".to_string()), name: self.name.clone() }))
+        }
+    }
+
+    fn hook(name: Option<&str>) -> BizHook {
+        BizHook {
+            id: None,
+            name: name.map(|x| x.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_gen_hooks() {
+        block_on(async {
+            let mut executor = MockExecutor { rb: Rbatis::new(), sqls: vec![] };
+            let table = hook(None);
+            assert!(table.before_insert(&mut executor).await.is_err());
+            let mut table = hook(Some(" a "));
+            let changed = table.before_insert(&mut executor).await.unwrap().unwrap();
+            assert_eq!(changed.name, Some("a".to_string()));
+            assert_eq!(table.name, Some(" a ".to_string()));
+            assert_eq!(executor.sqls, vec!["select 'validate'".to_string()]);
+            //not configured
+            table.after_insert(&mut executor).await.unwrap();
+            table.after_fetch(&mut executor).await.unwrap();
+            assert_eq!(table.name, Some(" A ".to_string()));
+            assert!(BizHook::before_delete(&mut executor, &Wrapper::new(&DriverType::Mysql)).await.is_err());
+            BizHook::before_delete(&mut executor, &Wrapper::new(&DriverType::Mysql).eq("id", 1)).await.unwrap();
+        });
+    }
+
+    #[test]
+    fn test_forward_hooks() {
+        block_on(async {
+            let mut executor = MockExecutor { rb: Rbatis::new(), sqls: vec![] };
+            let table = TableNoVersion { table: hook(None) };
+            assert!(table.before_insert(&mut executor).await.is_err());
+            let mut table = TableNoLogic { table: hook(Some("b")) };
+            assert!(table.before_insert(&mut executor).await.unwrap().is_some());
+            table.after_fetch(&mut executor).await.unwrap();
+            assert_eq!(table.table.name, Some("B".to_string()));
+            assert!(TableNoVersion::<BizHook>::before_delete(&mut executor, &Wrapper::new(&DriverType::Mysql)).await.is_err());
+            assert!(TableNoLogic::<BizHook>::before_delete(&mut executor, &Wrapper::new(&DriverType::Mysql)).await.is_err());
+            assert_eq!(executor.sqls.len(), 1);
+        });
+    }
+
+    #[test]
+    fn test_call_hooks() {
+        block_on(async {
+            let mut executor = MockExecutor { rb: Rbatis::new(), sqls: vec![] };
+            let table = BizManual { id: None, name: Some("a".to_string()) };
+            executor.save(&table, &[]).await.unwrap();
+            assert_eq!(executor.sqls[0], "insert into biz_manual (id,name) values (?,?)");
+            //the argument is not changed
+            assert_eq!(table.id, None);
+        });
+    }
+}
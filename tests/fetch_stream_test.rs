@@ -0,0 +1,113 @@
+#[cfg(test)]
+mod test {
+    use std::collections::VecDeque;
+
+    use futures::executor::block_on;
+    use futures::StreamExt;
+    use serde::de::DeserializeOwned;
+    use serde_json::{json, Value};
+
+    use rbatis::core::db::{DBExecResult, DriverType};
+    use rbatis::core::Error;
+    use rbatis::crud::CRUDMut;
+    use rbatis::crud_table;
+    use rbatis::executor::{ExecutorMut, RbatisRef};
+    use rbatis::rbatis::Rbatis;
+    use rbatis::wrapper::Wrapper;
+
+    /// return the pages in order,and record the sql and args
+    pub struct MockExecutor {
+        pub rb: Rbatis,
+        pub pages: VecDeque<Value>,
+        pub sqls: Vec<(String, Vec<Value>)>,
+    }
+
+    impl RbatisRef for MockExecutor {
+        fn get_rbatis(&self) -> &Rbatis {
+            &self.rb
+        }
+
+        fn driver_type(&self) -> rbatis::Result<DriverType> {
+            Ok(DriverType::Mysql)
+        }
+    }
+
+    #[rbatis::async_trait]
+    impl ExecutorMut for MockExecutor {
+        async fn exec(&mut self, _sql: &str, _args: &Vec<Value>) -> Result<DBExecResult, Error> {
+            Err(Error::from("not support exec"))
+        }
+
+        async fn fetch<T>(&mut self, sql: &str, args: &Vec<Value>) -> Result<T, Error> where T: DeserializeOwned {
+            self.sqls.push((sql.to_string(), args.clone()));
+            let page = self.pages.pop_front().unwrap_or(json!([]));
+            serde_json::from_value(page).map_err(|e| Error::from(e.to_string()))
+        }
+    }
+
+    impl CRUDMut for MockExecutor {}
+
+    #[crud_table(table_name:"biz_stream")]
+    #[derive(Clone, Debug)]
+    pub struct BizStream {
+        pub id: Option<i32>,
+        pub name: Option<String>,
+    }
+
+    fn executor(pages: Vec<Value>) -> MockExecutor {
+        MockExecutor {
+            rb: Rbatis::new(),
+            pages: pages.into(),
+            sqls: vec![],
+        }
+    }
+
+    #[test]
+    fn test_fetch_stream_by_wrapper() {
+        block_on(async {
+            let mut executor = executor(vec![
+                json!([{"id":1,"name":"a"},{"id":2,"name":"a"}]),
+                json!([{"id":3,"name":"a"}]),
+            ]);
+            let w = Wrapper::new(&DriverType::Mysql).eq("name", "a");
+            let ids: Vec<i32> = executor.fetch_stream_by_wrapper::<BizStream>(&w, 2)
+                .map(|x| x.unwrap().id.unwrap())
+                .collect()
+                .await;
+            assert_eq!(ids, vec![1, 2, 3]);
+            //the last chunk is not full,no more sql
+            assert_eq!(executor.sqls.len(), 2);
+            //keyset,not offset
+            let (sql, args) = &executor.sqls[1];
+            assert!(sql.contains("id > ?"), "{}", sql);
+            assert!(!sql.contains("offset"), "{}", sql);
+            assert_eq!(args, &vec![json!("a"), json!(2)]);
+        });
+    }
+
+    #[test]
+    fn test_fetch_stream_by_wrapper_end() {
+        block_on(async {
+            let mut executor = executor(vec![json!([{"id":1},{"id":2}])]);
+            let w = Wrapper::new(&DriverType::Mysql);
+            let items: Vec<rbatis::Result<BizStream>> = executor.fetch_stream_by_wrapper(&w, 2).collect().await;
+            assert_eq!(items.len(), 2);
+            //the full chunk need one more sql to find the end
+            assert_eq!(executor.sqls.len(), 2);
+        });
+    }
+
+    #[test]
+    fn test_fetch_stream_by_wrapper_check() {
+        block_on(async {
+            let mut executor = executor(vec![]);
+            let w = Wrapper::new(&DriverType::Mysql).order_by(true, &["name"]);
+            let items: Vec<rbatis::Result<BizStream>> = executor.fetch_stream_by_wrapper(&w, 2).collect().await;
+            assert_eq!(items.len(), 1);
+            assert!(items[0].is_err());
+            let items: Vec<rbatis::Result<BizStream>> = executor.fetch_stream_by_wrapper(&Wrapper::new(&DriverType::Mysql), 0).collect().await;
+            assert!(items[0].is_err());
+            assert!(executor.sqls.is_empty());
+        });
+    }
+}
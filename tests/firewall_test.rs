@@ -0,0 +1,68 @@
+#[cfg(test)]
+mod test {
+    use rbatis::core::db::DriverType;
+    use rbatis::plugin::firewall::{FirewallRule, SqlFirewall};
+    use rbatis::plugin::intercept::{InterceptContext, SqlOperation};
+
+    fn context(operation: SqlOperation) -> InterceptContext {
+        InterceptContext {
+            operation,
+            table: None,
+            in_transaction: false,
+            is_prepared: true,
+        }
+    }
+
+    fn rules(firewall: &SqlFirewall, sql: &str) -> Vec<FirewallRule> {
+        firewall.check(&DriverType::Mysql, &context(SqlOperation::Exec), sql)
+            .into_iter()
+            .map(|x| x.rule)
+            .collect()
+    }
+
+    #[test]
+    fn test_where() {
+        let firewall = SqlFirewall::default();
+        assert_eq!(rules(&firewall, "delete from biz_activity"), vec![FirewallRule::NoWhere]);
+        assert_eq!(rules(&firewall, "update biz_activity set name = ? where 1=1"), vec![FirewallRule::Tautology]);
+        assert_eq!(rules(&firewall, "delete from biz_activity where (id = ? or 'a' = 'a')"), vec![FirewallRule::Tautology]);
+        assert_eq!(rules(&firewall, "delete from biz_activity where id = id"), vec![FirewallRule::Tautology]);
+        assert_eq!(rules(&firewall, "delete from biz_activity where 2 > 1 and true"), vec![FirewallRule::Tautology]);
+        assert_eq!(rules(&firewall, "select * from biz_activity where name like '%%'"), vec![FirewallRule::Tautology]);
+        assert!(rules(&firewall, "delete from biz_activity where id = ? and 1 = 1").is_empty());
+        assert!(rules(&firewall, "delete from biz_activity where age between 1 and 2").is_empty());
+        assert!(rules(&firewall, "update biz_activity set remark = ' where 1=1' where id = ?").is_empty());
+    }
+
+    #[test]
+    fn test_statement() {
+        let firewall = SqlFirewall::default();
+        assert_eq!(rules(&firewall, "select * from a where id = ?;drop table a"), vec![FirewallRule::MultiStatement, FirewallRule::Ddl]);
+        assert_eq!(rules(&firewall, "truncate table a"), vec![FirewallRule::Ddl]);
+        assert!(rules(&firewall, "select * from a where remark = ';drop table a';").is_empty());
+        assert!(rules(&firewall.clone().allow(FirewallRule::Ddl), "create table if not exists a (id int)").is_empty());
+    }
+
+    #[test]
+    fn test_table() {
+        let firewall = SqlFirewall::default().big_table("biz_log").deny_table("sys_secret");
+        assert_eq!(rules(&firewall, "select * from biz_log where id > ?"), vec![FirewallRule::SelectWithoutLimit]);
+        assert!(rules(&firewall, "select * from biz_log where id > ? limit 10").is_empty());
+        assert!(firewall.check(&DriverType::Mysql, &context(SqlOperation::PageCount), "select count(1) from biz_log").is_empty());
+        //aggregate only select is one row
+        assert!(rules(&firewall, "select count(1) from biz_log where id > ?").is_empty());
+        assert!(rules(&firewall, "select sum(amount) as total,max(id) m from biz_log").is_empty());
+        assert_eq!(rules(&firewall, "select count(1),name from biz_log"), vec![FirewallRule::SelectWithoutLimit]);
+        assert_eq!(rules(&firewall, "select count(1) from biz_log group by name"), vec![FirewallRule::SelectWithoutLimit]);
+        assert_eq!(rules(&firewall, "select count(1) + id from biz_log"), vec![FirewallRule::SelectWithoutLimit]);
+        //the count/aggregate of CRUD
+        assert!(firewall.check(&DriverType::Mysql, &context(SqlOperation::Aggregate), "select status,count(1) from biz_log group by status").is_empty());
+        let violations = firewall.check(&DriverType::Mysql, &context(SqlOperation::Fetch), "select * from a join db.sys_secret s on a.id = s.id limit 1");
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].to_string(), "TableDenied(table:sys_secret)");
+        let firewall = SqlFirewall::default().allow_table("biz_activity");
+        assert!(rules(&firewall, "insert into biz_activity (id,name) values (?,?) on duplicate key update name = ?").is_empty());
+        assert!(rules(&firewall, "select extract(year from create_time) from biz_activity").is_empty());
+        assert_eq!(rules(&firewall, "select * from biz_log"), vec![FirewallRule::TableDenied]);
+    }
+}
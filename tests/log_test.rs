@@ -17,3 +17,80 @@ mod test {
         wg.exit_and_wait();
     }
 }
+
+#[cfg(test)]
+mod event_test {
+    use std::io::Write;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    use rbatis::core::Error;
+    use rbatis::plugin::intercept::SqlOperation;
+    use rbatis::plugin::log::{JsonLogPlugin, LogPlugin, SqlLogEvent, SqlLogPhase};
+    use serde_json::{json, Value};
+
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn event<'a>(phase: SqlLogPhase, args: &'a [Value], error: Option<&'a Error>) -> SqlLogEvent<'a> {
+        SqlLogEvent {
+            phase,
+            operation: SqlOperation::Exec,
+            sql: "update biz_activity set name = ? where id = ?",
+            args,
+            rows_affected: if phase == SqlLogPhase::Response && error.is_none() { Some(1) } else { None },
+            elapsed: if phase == SqlLogPhase::Response { Some(Duration::from_micros(1500)) } else { None },
+            error,
+            tx_id: Some(7),
+        }
+    }
+
+    #[test]
+    fn test_text() {
+        let args = vec![json!("a"), json!(1)];
+        let text = event(SqlLogPhase::Request, &args, None).to_text();
+        assert!(text.starts_with("Exec   ==> update biz_activity set name = ? where id = ?\n"));
+        assert!(text.ends_with("[rbatis] [tx:7] Args   ==> [\"a\",1]"));
+        assert_eq!(event(SqlLogPhase::Response, &args, None).to_text(), "RowsAffected <== 1 (1.5ms)");
+        let e = Error::from("timeout");
+        assert_eq!(event(SqlLogPhase::Response, &args, Some(&e)).to_text(), "ReturnErr  <== timeout (1.5ms)");
+    }
+
+    #[test]
+    fn test_json_lines() {
+        let buffer = Buffer::default();
+        let mut plugin = JsonLogPlugin::new(buffer.clone());
+        let args = vec![json!("a"), json!(1)];
+        let e = Error::from("timeout");
+        plugin.log_sql(&event(SqlLogPhase::Request, &args, None));
+        plugin.log_sql(&event(SqlLogPhase::Response, &args, Some(&e)));
+        plugin.level_filter = log::LevelFilter::Error;
+        plugin.log_sql(&event(SqlLogPhase::Response, &args, None));
+        let text = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let lines: Vec<Value> = text.lines().map(|x| serde_json::from_str(x).unwrap()).collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0], json!({
+            "phase": "request",
+            "operation": "exec",
+            "sql": "update biz_activity set name = ? where id = ?",
+            "args": ["a", 1],
+            "rows_affected": null,
+            "elapsed_us": null,
+            "error": null,
+            "tx_id": 7
+        }));
+        assert_eq!(lines[1]["phase"], json!("response"));
+        assert_eq!(lines[1]["elapsed_us"], json!(1500));
+        assert_eq!(lines[1]["error"], json!("timeout"));
+    }
+}
@@ -0,0 +1,43 @@
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use rbatis::plugin::intercept::{InterceptContext, SqlOperation};
+    use rbatis::plugin::metrics::{sql_fingerprint, MetricsPlugin, RbatisMetricsPlugin};
+
+    #[test]
+    fn test_fingerprint() {
+        assert_eq!(sql_fingerprint("select * from a where id in ($1,$2, $3) and name = 'a''b'"), "select * from a where id in(?) and name = ?");
+        assert_eq!(sql_fingerprint("SELECT  *\n FROM a WHERE id = @p1 and t1.b = 10.5 limit 10"), "select * from a where id = ? and t1.b = ? limit ?");
+        assert_eq!(sql_fingerprint("insert into a (id,name) values (?,?),(?,?),(?, ?)"), "insert into a(id,name) values(?)");
+        assert_eq!(sql_fingerprint("select \"Name 1\" from a where b = `c 2`"), "select \"Name 1\" from a where b = `c 2`");
+    }
+
+    #[test]
+    fn test_metrics() {
+        let plugin = RbatisMetricsPlugin::new(&[0.01, 0.1]);
+        let context = InterceptContext {
+            operation: SqlOperation::Fetch,
+            table: None,
+            in_transaction: false,
+            is_prepared: true,
+        };
+        plugin.record_sql(&context, "select * from a where id = ?", Duration::from_millis(5), 1, false);
+        plugin.record_sql(&context, "select * from a where id = 1", Duration::from_millis(50), 0, true);
+        plugin.record_acquire(Duration::from_millis(1), false);
+        let snapshot = plugin.snapshot();
+        assert_eq!(snapshot.statements.len(), 1);
+        let item = &snapshot.statements[0];
+        assert_eq!(item.calls, 2);
+        assert_eq!(item.errors, 1);
+        assert_eq!(item.rows, 1);
+        assert_eq!(item.latency.buckets, vec![(0.01, 1), (0.1, 2)]);
+        assert_eq!(snapshot.acquire_wait.count, 1);
+        let text = snapshot.to_prometheus();
+        assert!(text.contains("rbatis_sql_calls_total{sql=\"select * from a where id = ?\"} 2\n"));
+        assert!(text.contains("rbatis_sql_duration_seconds_bucket{sql=\"select * from a where id = ?\",le=\"+Inf\"} 2\n"));
+        assert!(text.contains("rbatis_sql_duration_seconds_count{sql=\"select * from a where id = ?\"} 2\n"));
+        assert!(text.contains("rbatis_pool_acquire_seconds_bucket{le=\"0.01\"} 1\n"));
+        assert!(text.contains("rbatis_pool_acquire_seconds_count 1\n"));
+    }
+}
@@ -1,6 +1,6 @@
 #[cfg(test)]
 mod test {
-    use rbatis::plugin::page::{IPage, IPageRequest, Page, RbatisReplacePagePlugin};
+    use rbatis::plugin::page::{IPage, IPageRequest, Page, PageRequest, RbatisReplacePagePlugin};
 
     #[test]
     pub fn test_page() {
@@ -24,4 +24,50 @@ mod test {
             .make_count_sql("biz_activity where id = 1 and order by id DESC and order by id DESC");
         println!("sql:{}", sql);
     }
+
+    #[test]
+    fn test_page_map() {
+        let mut page: Page<i32> = Page::new_total(2, 10, 25);
+        page.pages = page.get_pages();
+        page.records = vec![1, 2, 3];
+        assert_eq!(page.has_next(), true);
+        assert_eq!(page.has_prev(), true);
+        assert_eq!(page.first_item_index(), 10);
+
+        let p: Page<String> = page.clone().map(|x| x.to_string());
+        assert_eq!(p.records, vec!["1".to_string(), "2".to_string(), "3".to_string()]);
+        assert_eq!(p.total, 25);
+        assert_eq!(p.pages, 3);
+
+        let r: Result<Page<u8>, String> = page.clone().try_map(|x| Ok(x as u8));
+        assert_eq!(r.unwrap().records, vec![1u8, 2, 3]);
+        let r: Result<Page<u8>, String> = page.try_map(|x| if x == 2 { Err("fail".to_string()) } else { Ok(x as u8) });
+        assert_eq!(r.err().unwrap(), "fail");
+
+        let last: Page<i32> = Page::new_total(3, 10, 25);
+        assert_eq!(last.has_next(), false);
+    }
+
+    #[test]
+    fn test_page_request_max_size() {
+        let req = PageRequest::new_option(&Some(1), &Some(1000000000));
+        assert_eq!(req.page_size, rbatis::plugin::page::DEFAULT_MAX_PAGE_SIZE);
+        let req = PageRequest::new_option_max(&None, &Some(100), 50);
+        assert_eq!(req.page_size, 50);
+        assert_eq!(req.page_no, 1);
+        assert_eq!(req.max_page_size(), 50);
+        let req: PageRequest = serde_json::from_str(r#"{"total":0,"page_no":1,"page_size":10,"search_count":true,"max_page_size":1000000}"#).unwrap();
+        assert_eq!(req.max_page_size(), rbatis::plugin::page::DEFAULT_MAX_PAGE_SIZE);
+        assert_eq!(req.page_size, 10);
+        //deserialize keep the page_size,clamp it by set_max_page_size()
+        let req: PageRequest = serde_json::from_str(r#"{"total":0,"page_no":1,"page_size":1000000000,"search_count":true}"#).unwrap();
+        assert_eq!(req.page_size, 1000000000);
+        assert_eq!(req.set_max_page_size(100).page_size, 100);
+    }
+
+    #[test]
+    fn test_first_item_index_overflow() {
+        let page: Page<i32> = Page::new(u64::MAX, 10);
+        assert_eq!(page.first_item_index(), u64::MAX);
+    }
 }
@@ -0,0 +1,69 @@
+#[cfg(test)]
+mod test {
+    use rbatis::core::db::DriverType;
+    use rbatis::crud::CRUDTable;
+    use rbatis::crud_table;
+    use rbatis::plugin::logic_delete::TableNoLogic;
+    use rbatis::plugin::redact::{arg_columns, RedactPolicy};
+    use rbatis::plugin::version_lock::TableNoVersion;
+    use serde_json::json;
+
+    #[crud_table]
+    #[derive(Clone, Debug)]
+    pub struct User {
+        pub id: Option<i64>,
+        pub name: Option<String>,
+        #[crud_table(sensitive)]
+        pub password: Option<String>,
+    }
+
+    /// #[crud_table(sensitive)] is a helper of derive(CRUDTable) too
+    #[derive(rbatis::CRUDTable, Clone, Debug, serde::Serialize, serde::Deserialize)]
+    pub struct Account {
+        pub id: Option<i64>,
+        #[crud_table(sensitive)]
+        pub token: Option<String>,
+    }
+
+    /// the columns of args,'?' is unknown
+    fn columns(driver_type: &DriverType, sql: &str) -> String {
+        arg_columns(driver_type, sql).iter()
+            .map(|x| x.as_deref().unwrap_or("?"))
+            .collect::<Vec<&str>>()
+            .join(",")
+    }
+
+    #[test]
+    fn test_arg_columns() {
+        assert_eq!(columns(&DriverType::Mysql, "update user set name = ?,`password` = ? where id in (?,?) and age between ? and ?"),
+                   "name,password,id,id,age,age");
+        assert_eq!(columns(&DriverType::Mysql, "insert into user (id,name,password) values (?,?,?),(?,?,lower(?))"),
+                   "id,name,password,id,name,password");
+        assert_eq!(columns(&DriverType::Postgres, "select * from user where u.token = $2 and lower(name) like lower($1) limit $3"),
+                   "name,token,?");
+        assert_eq!(columns(&DriverType::Mssql, "select * from [user] where [secret] <> @p1"),
+                   "secret");
+    }
+
+    #[test]
+    fn test_redact_args() {
+        assert_eq!(User::sensitive_columns(), vec!["password".to_string()]);
+        assert_eq!(TableNoVersion::<User>::sensitive_columns(), vec!["password".to_string()]);
+        assert_eq!(TableNoLogic::<User>::sensitive_columns(), vec!["password".to_string()]);
+        assert_eq!(Account::sensitive_columns(), vec!["token".to_string()]);
+        let policy = RedactPolicy::default()
+            .table::<User>()
+            .pattern("*token")
+            .position(3)
+            .max_len(4)
+            .max_blob_len(64);
+        let args = policy.redact_args(&DriverType::Mysql,
+                                      "update user set password = ?,api_token = ?,name = ? where id = ? and data = ?",
+                                      &vec![json!("123456"), json!("t"), json!("abcdefg"), json!(1), json!(vec![1u8; 100])]);
+        assert_eq!(args, vec![json!("******"), json!("******"), json!("abcd...(7 chars)"), json!("******"), json!("<blob 100 bytes>")]);
+
+        //blob is not truncated by default
+        let args = RedactPolicy::default().redact_args(&DriverType::Postgres, "select * from user where id = any($1)", &vec![json!(vec![1u8; 100])]);
+        assert_eq!(args, vec![json!(vec![1u8; 100])]);
+    }
+}
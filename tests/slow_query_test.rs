@@ -0,0 +1,49 @@
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use rbatis::core::db::DriverType;
+    use rbatis::plugin::intercept::SqlOperation;
+    use rbatis::plugin::slow_query::{make_explain_sql, RbatisSlowQueryPlugin, SlowQuery, SlowQueryPlugin};
+    use rbatis::rbatis::Rbatis;
+
+    fn query(sql: &str, millis: u64) -> SlowQuery {
+        SlowQuery {
+            sql: sql.to_string(),
+            args: vec![],
+            elapsed: Duration::from_millis(millis),
+            operation: SqlOperation::Fetch,
+            table: None,
+            in_transaction: false,
+            explain: None,
+        }
+    }
+
+    #[test]
+    fn test_top_n() {
+        let rb = Rbatis::new();
+        let mut plugin = RbatisSlowQueryPlugin::new(Duration::from_millis(100));
+        plugin.top_n = 3;
+        for (sql, millis) in [("a", 200), ("b", 500), ("c", 100), ("d", 300), ("e", 150)].iter() {
+            plugin.record(&rb, query(sql, *millis));
+        }
+        let slowest: Vec<String> = plugin.slowest().iter().map(|x| x.sql.clone()).collect();
+        assert_eq!(slowest, vec!["b", "d", "a"]);
+        plugin.clear();
+        assert_eq!(plugin.slowest().len(), 0);
+
+        let mut rb = Rbatis::new();
+        assert_eq!(rb.slow_queries().len(), 0);
+        rb.set_slow_query_plugin(Some(RbatisSlowQueryPlugin::new(Duration::from_millis(100))));
+        rb.slow_query_plugin().unwrap().record(&rb, query("a", 200));
+        assert_eq!(rb.slow_queries().len(), 1);
+    }
+
+    #[test]
+    fn test_explain_sql() {
+        assert_eq!(make_explain_sql(&DriverType::Mysql, " select * from a"), Some("explain select * from a".to_string()));
+        assert_eq!(make_explain_sql(&DriverType::Sqlite, "SELECT 1"), Some("explain query plan SELECT 1".to_string()));
+        assert_eq!(make_explain_sql(&DriverType::Postgres, "update a set b = 1"), None);
+        assert_eq!(make_explain_sql(&DriverType::Mssql, "select 1"), None);
+    }
+}
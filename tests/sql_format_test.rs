@@ -0,0 +1,44 @@
+#[cfg(test)]
+mod test {
+    use rbatis::core::db::DriverType;
+    use rbatis::sql::{format_sql, sql_literal};
+    use serde_json::json;
+
+    #[test]
+    fn test_format_mysql() {
+        let sql = format_sql(&DriverType::Mysql,
+                             "select * from a where name = ? and remark = 'why?' and `b?` = ? and path = ? /* ? */ and id in (?,?)",
+                             &vec![json!("it's"), json!(null), json!("c:\\a"), json!(1), json!(true)]);
+        assert_eq!(sql, "select * from a where name = 'it''s' and remark = 'why?' and `b?` = NULL and path = 'c:\\\\a' /* ? */ and id in (1,true)");
+    }
+
+    #[test]
+    fn test_format_pg() {
+        let args: Vec<serde_json::Value> = (1..=10).map(|x| json!(x)).collect();
+        let sql = format_sql(&DriverType::Postgres,
+                             "select * from a where a = $1 and b = $10 and c = '$1' and d ? 'k' and e = $2::text",
+                             &args);
+        assert_eq!(sql, "select * from a where a = 1 and b = 10 and c = '$1' and d ? 'k' and e = 2::text");
+        assert_eq!(format_sql(&DriverType::Postgres, "select * from a where id = any($1) and t = $2", &vec![json!(["a", "b"]), json!("2021-01-01T00:00:00")]),
+                   "select * from a where id = any(ARRAY['a','b']) and t = '2021-01-01T00:00:00'");
+        //Vec<u8> is bytea,the int array of any() is an array
+        assert_eq!(format_sql(&DriverType::Postgres, "update a set data = $1 where id = any($2) and code <> all ( $3 )", &vec![json!([1, 255]), json!([1, 2]), json!([3])]),
+                   "update a set data = '\\x01ff' where id = any(ARRAY[1,2]) and code <> all ( ARRAY[3] )");
+        assert_eq!(sql_literal(&DriverType::Postgres, &json!([1, 255])), "'\\x01ff'");
+        assert_eq!(sql_literal(&DriverType::Postgres, &json!([1, 256])), "ARRAY[1,256]");
+        //dollar quoted string and comment
+        assert_eq!(format_sql(&DriverType::Postgres, "select $$ $1 $$, $1 -- $2\n, $2", &vec![json!(1), json!(2)]),
+                   "select $$ $1 $$, 1 -- $2\n, 2");
+    }
+
+    #[test]
+    fn test_format_mssql_sqlite() {
+        assert_eq!(format_sql(&DriverType::Mssql, "select * from [a@p1] where name = @p1 and ok = @p2", &vec![json!("张三"), json!(false)]),
+                   "select * from [a@p1] where name = N'张三' and ok = 0");
+        assert_eq!(format_sql(&DriverType::Sqlite, "insert into a (data,json) values (?,?)", &vec![json!([1, 255]), json!({"a": "b'c"})]),
+                   "insert into a (data,json) values (X'01FF','{\"a\":\"b''c\"}')");
+        //missing arg keep the placeholder
+        assert_eq!(format_sql(&DriverType::Sqlite, "select ?,?", &vec![json!(1)]), "select 1,?");
+        assert_eq!(sql_literal(&DriverType::Mssql, &json!([1, 2])), "0x0102");
+    }
+}
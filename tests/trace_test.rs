@@ -0,0 +1,20 @@
+#[cfg(all(test, feature = "tracing"))]
+mod test {
+    use rbatis::core::db::DriverType;
+    use rbatis::plugin::trace::{db_operation, db_system};
+
+    #[test]
+    fn test_db_system() {
+        assert_eq!(db_system(&DriverType::Mysql), "mysql");
+        assert_eq!(db_system(&DriverType::Postgres), "postgresql");
+        assert_eq!(db_system(&DriverType::Sqlite), "sqlite");
+        assert_eq!(db_system(&DriverType::Mssql), "mssql");
+    }
+
+    #[test]
+    fn test_db_operation() {
+        assert_eq!(db_operation("  SELECT * from biz_activity"), "select");
+        assert_eq!(db_operation("insert into biz_activity(id) values (?)"), "insert");
+        assert_eq!(db_operation("with t as (select 1) select * from t"), "with");
+    }
+}
@@ -0,0 +1,16 @@
+#[macro_use]
+extern crate rbatis;
+
+use rbatis::core::db::DriverType;
+use rbatis::wrapper::Wrapper;
+
+#[crud_table]
+#[derive(Clone, Debug)]
+pub struct BizActivity {
+    pub id: Option<String>,
+    pub name: Option<String>,
+}
+
+fn main() {
+    let _w = Wrapper::new(&DriverType::Mysql).eq(BizActivity::COLUMNS.nmae, "a");
+}
@@ -0,0 +1,11 @@
+#[macro_use]
+extern crate rbatis;
+
+#[crud_table(table_columns:"id,nmae")]
+#[derive(Clone, Debug)]
+pub struct BizActivity {
+    pub id: Option<String>,
+    pub name: Option<String>,
+}
+
+fn main() {}
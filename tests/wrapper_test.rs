@@ -146,4 +146,393 @@ mod test {
         assert_eq!(w2.sql.contains("b = $1"), true);
         assert_eq!(w2.sql.contains("a = $4"), true);
     }
+
+    #[test]
+    fn test_typed_column() {
+        use rbatis::column::Column;
+        struct Table {}
+        const ID: Column<Table, Option<i64>> = Column::new("id");
+        const NAME: Column<Table, Option<String>> = Column::new("name");
+        let w = Wrapper::new(&DriverType::Postgres)
+            .eq(NAME, "a")
+            .eq(NAME, "b".to_string())
+            .in_array(ID, &[1, 2])
+            .is_not_null(ID)
+            .order_by(true, &[&ID]);
+        assert_eq!(w.sql.trim(), "name = $1 and name = $2 and id in ( $3 , $4 ) and id is not NULL order by id asc");
+        assert_eq!(w.args, vec![json!("a"), json!("b"), json!(1), json!(2)]);
+    }
+
+    #[test]
+    fn test_group() {
+        let w = Wrapper::new(&DriverType::Postgres)
+            .eq("a", 1)
+            .and_group(|w| w.eq("b", 2).or().eq("c", 3))
+            .or_group(|w| w.eq("d", 4).eq("e", 5))
+            .and_group(|w| w)
+            .and_group(|w| w.do_if(false, |w| w.eq("f", 6)))
+            .eq("g", 7);
+        assert_eq!(w.sql, "a = $1 and (b = $2 or c = $3) or (d = $4 and e = $5) and g = $6");
+        assert_eq!(w.args, vec![json!(1), json!(2), json!(3), json!(4), json!(5), json!(7)]);
+
+        let w = Wrapper::new(&DriverType::Mysql)
+            .or_group(|w| w.eq("a", 1).or().and_group(|w| w.eq("b", 2).or().eq("c", 3)))
+            .order_by(true, &["id"]);
+        assert_eq!(w.sql, "(a = ? or (b = ? or c = ?)) order by id asc");
+    }
+
+    #[test]
+    fn test_sql_field() {
+        //the old fields are rendered for w.driver_type,and refreshed when w changed
+        let w = Wrapper::new(&DriverType::Postgres).eq("a", 1).or().eq("b", 2);
+        assert_eq!(w.sql, "a = $1 or b = $2");
+        let w = w.group().eq("c", 3).order_by(true, &["id"]);
+        assert_eq!(w.sql, "(a = $1 or b = $2) and c = $3 order by id asc");
+        assert_eq!(w.args, vec![json!(1), json!(2), json!(3)]);
+        let w = w.limit(1);
+        assert_eq!(w.sql, "(a = $1 or b = $2) and c = $3 order by id asc limit 1");
+    }
+
+    #[test]
+    fn test_is_empty() {
+        let w = Wrapper::new(&DriverType::Mysql);
+        assert!(w.is_empty());
+        let w = w.and().or_group(|w| w.or()).push_sql(" ");
+        assert!(w.is_empty());
+        assert!(!w.clone().eq("a", 1).is_empty());
+        assert!(!w.clone().order_by(true, &["id"]).is_empty());
+        assert!(!w.limit(1).is_empty());
+    }
+
+    #[test]
+    fn test_set_format() {
+        let w = Wrapper::new(&DriverType::Mysql).eq("id", 1);
+        assert_eq!(w.sql, "id = ?");
+        //the cache of w.sql is cleared
+        let w = w.set_format("id", |s| format!("{}::uuid", s));
+        assert_eq!(w.sql, "id = ?::uuid");
+        assert!(w.formats().contains_key("id"));
+        assert_eq!(w.conditions().len(), 1);
+        assert_eq!(w.driver_type(), &DriverType::Mysql);
+    }
+
+    #[test]
+    fn test_build() {
+        let mut w = Wrapper::new(&DriverType::Postgres);
+        for i in 0..10 {
+            w = w.eq("a", i);
+        }
+        let w = Wrapper::new(&DriverType::Postgres)
+            .push_wrapper(&w)
+            .and()
+            .push("b = $1 or c = $2", &[10, 11])
+            .limit(5)
+            .order_by(false, &["id"])
+            .eq("d", 12)
+            .group_by(&["id"]);
+        let (sql, args) = w.build(&DriverType::Postgres);
+        assert_eq!(sql, "a = $1 and a = $2 and a = $3 and a = $4 and a = $5 and a = $6 and a = $7 and a = $8 and a = $9 and a = $10 and b = $11 or c = $12 and d = $13 group by id order by id desc limit 5");
+        assert_eq!(args.len(), 13);
+        assert_eq!(args[12], json!(12));
+
+        let w = Wrapper::new(&DriverType::Postgres)
+            .eq("a", 1)
+            .and()
+            .push("b = '$1' and c = $1", &[2])
+            .order_by(true, &["id"])
+            .limit(1);
+        let (sql, args) = w.build(&DriverType::Mssql);
+        assert_eq!(sql, "a = @p1 and b = '$1' and c = @p2 order by id asc offset 0 rows fetch next 1 rows only");
+        assert_eq!(args, vec![json!(1), json!(2)]);
+        let (sql, _) = w.build(&DriverType::Mysql);
+        assert_eq!(sql, "a = ? and b = '$1' and c = ? order by id asc limit 1");
+    }
+
+    #[test]
+    fn test_join() {
+        use rbatis::crud::CRUDTable;
+        use std::collections::HashMap;
+        #[derive(serde::Serialize, serde::Deserialize)]
+        struct User {}
+        impl CRUDTable for User {
+            fn table_columns() -> String {
+                "id,name".to_string()
+            }
+            fn formats(_: &DriverType) -> HashMap<String, fn(arg: &str) -> String> {
+                let mut m: HashMap<String, fn(arg: &str) -> String> = HashMap::new();
+                m.insert("id".to_string(), |arg| format!("{}::uuid", arg));
+                m
+            }
+        }
+        let w = Wrapper::new(&DriverType::Postgres)
+            .alias("a")
+            .left_join_as::<User>("u", "a.user_id = u.id")
+            .inner_join::<User>("a.user_id = user.id")
+            .eq("u.id", "1")
+            .eq("a.id", "2");
+        assert_eq!(w.make_from_sql("biz_activity"), "biz_activity a left join user u on a.user_id = u.id inner join user on a.user_id = user.id");
+        assert_eq!(w.sql(), "u.id = $1::uuid and a.id = $2");
+    }
+
+    #[test]
+    fn test_sub() {
+        use rbatis::crud::CRUDTable;
+        #[derive(serde::Serialize, serde::Deserialize)]
+        struct User {}
+        impl CRUDTable for User {
+            fn table_columns() -> String {
+                "id,name".to_string()
+            }
+        }
+        let sub = Wrapper::new(&DriverType::Postgres)
+            .select::<User>(&["id"])
+            .eq("name", "a")
+            .in_array("status", &[1, 2]);
+        let w = Wrapper::new(&DriverType::Postgres)
+            .eq("a", 1)
+            .in_sub("user_id", sub.clone()).unwrap()
+            .or()
+            .not_exists(Wrapper::new(&DriverType::Postgres).select::<User>(&[]).push_sql("id = a.user_id").limit(1)).unwrap()
+            .not_in_sub("id", sub).unwrap()
+            .eq("b", 2);
+        let (sql, args) = w.build(&DriverType::Postgres);
+        assert_eq!(sql, "a = $1 and user_id in (select id from user where name = $2 and status in ( $3 , $4 )) or not exists (select id,name from user where id = a.user_id limit 1) and id not in (select id from user where name = $5 and status in ( $6 , $7 )) and b = $8");
+        assert_eq!(args, vec![json!(1), json!("a"), json!(1), json!(2), json!("a"), json!(1), json!(2), json!(2)]);
+        let (sql, _) = w.build(&DriverType::Mssql);
+        assert_eq!(sql.contains("name = @p5 and status in ( @p6 , @p7 )) and b = @p8"), true);
+        //offset of mssql need order by
+        assert_eq!(sql.contains("where id = a.user_id order by (select null) offset 0 rows fetch next 1 rows only)"), true, "{}", sql);
+        //sub wrapper without select
+        let r = Wrapper::new(&DriverType::Postgres).in_sub("id", Wrapper::new(&DriverType::Postgres).eq("a", 1));
+        assert!(r.is_err());
+        assert!(Wrapper::new(&DriverType::Postgres).exists(Wrapper::new(&DriverType::Postgres)).is_err());
+    }
+
+    #[test]
+    fn test_filter() {
+        use rbatis::WrapperFilter;
+        #[derive(WrapperFilter)]
+        struct ActivityQuery {
+            #[filter(op = "like")]
+            name: Option<String>,
+            #[filter(op = "ge", column = "create_time")]
+            create_time_start: Option<String>,
+            #[filter(op = "in", column = "status")]
+            status_in: Option<Vec<i32>>,
+            r#type: Option<i32>,
+            #[filter(skip)]
+            page_no: u64,
+            delete_flag: i32,
+        }
+        let q = ActivityQuery {
+            name: Some("a".to_string()),
+            create_time_start: None,
+            status_in: Some(vec![1, 2]),
+            r#type: Some(3),
+            page_no: 1,
+            delete_flag: 0,
+        };
+        assert_eq!(q.page_no, 1);
+        let w = Wrapper::new(&DriverType::Postgres)
+            .filter(&q)
+            .order_by(false, &["id"]);
+        assert_eq!(w.sql(), "name like $1 escape '!' and status in ( $2 , $3 ) and type = $4 and delete_flag = $5 order by id desc");
+        assert_eq!(w.args(), vec![json!("%a%"), json!(1), json!(2), json!(3), json!(0)]);
+    }
+
+    #[test]
+    fn test_order_by_spec() {
+        use rbatis::wrapper::SortError;
+        let w = Wrapper::new(&DriverType::Postgres)
+            .eq("a", 1)
+            .order_by_spec("name, -create_time nulls last,+id", &["id", "name", "create_time"])
+            .unwrap();
+        assert_eq!(w.sql(), "a = $1 order by name asc,create_time desc nulls last,id asc");
+        let (sql, _) = w.build(&DriverType::Mysql);
+        assert_eq!(sql, "a = ? order by name asc,case when create_time is null then 1 else 0 end,create_time desc,id asc");
+
+        let w = Wrapper::new(&DriverType::Postgres).order_by_spec("", &["id"]).unwrap();
+        assert_eq!(w.sql(), "");
+        let e = Wrapper::new(&DriverType::Postgres).order_by_spec("id;drop table user", &["id"]).unwrap_err();
+        assert_eq!(e, SortError::UnknownColumn("id;drop".to_string()));
+        let e = Wrapper::new(&DriverType::Postgres).order_by_spec("id,,name", &["id", "name"]).unwrap_err();
+        assert_eq!(e, SortError::InvalidSpec("".to_string()));
+        let e = Wrapper::new(&DriverType::Postgres).order_by_spec("id desc", &["id"]).unwrap_err();
+        assert_eq!(e, SortError::InvalidSpec("id desc".to_string()));
+        let w = Wrapper::new(&DriverType::Mysql).order_by_spec("-id nulls first", &["id"]).unwrap();
+        assert_eq!(w.sql(), "order by case when id is null then 0 else 1 end,id desc");
+    }
+
+    #[test]
+    fn test_full_text() {
+        use rbatis::wrapper::FullTextMode;
+        let w = Wrapper::new(&DriverType::Postgres)
+            .eq("a", 1)
+            .full_text(&["title", "content"], "rust orm", FullTextMode::Natural)
+            .full_text(&["title"], " ", FullTextMode::Natural)
+            .order_by_full_text(&["title", "content"], "rust orm", FullTextMode::Natural)
+            .order_by(false, &["id"]);
+        let (sql, args) = w.build(&DriverType::Postgres);
+        assert_eq!(sql, "a = $1 and to_tsvector(coalesce(title,'') || ' ' || coalesce(content,'')) @@ plainto_tsquery($2) order by ts_rank(to_tsvector(coalesce(title,'') || ' ' || coalesce(content,'')), plainto_tsquery($3)) desc,id desc");
+        assert_eq!(args, vec![json!(1), json!("rust orm"), json!("rust orm")]);
+        let (sql, _) = w.build(&DriverType::Mysql);
+        assert_eq!(sql, "a = ? and match (title,content) against (? in natural language mode) order by match (title,content) against (? in natural language mode) desc,id desc");
+        let (sql, args) = w.build(&DriverType::Sqlite);
+        assert_eq!(sql, "a = ? and (title match ? or content match ?) order by rank asc,id desc");
+        assert_eq!(args[1], json!("\"rust\" \"orm\""));
+        let (sql, _) = w.build(&DriverType::Mssql);
+        assert_eq!(sql, "a = @p1 and freetext((title,content), @p2) order by id desc");
+
+        let w = Wrapper::new(&DriverType::Mssql)
+            .full_text(&["title"], "say \"hi\"", FullTextMode::Phrase);
+        assert_eq!(w.sql(), "contains((title), @p1)");
+        assert_eq!(w.args(), vec![json!("\"say \"\"hi\"\"\"")]);
+        let (sql, args) = w.build(&DriverType::Mysql);
+        assert_eq!(sql, "match (title) against (? in boolean mode)");
+        assert_eq!(args, vec![json!("\"say hi\"")]);
+    }
+
+    #[test]
+    fn test_json() {
+        let w = Wrapper::new(&DriverType::Postgres)
+            .json_eq("attrs", "color", "red")
+            .json_eq("attrs", "size.width", 10)
+            .json_contains("attrs", json!({"a": 1}))
+            .json_has_key("attrs", "size.height")
+            .json_has_key("attrs", "$.color")
+            .json_array_contains("attrs", "tags", "new")
+            .json_array_contains("tags", "$", 1);
+        let (sql, args) = w.build(&DriverType::Postgres);
+        assert_eq!(sql, "attrs #>> $1::text[] = $2 and attrs #>> $3::text[] = $4 and attrs @> $5::jsonb and (attrs #> $6::text[]) ? $7 and attrs ? $8 and attrs #> $9::text[] @> $10::jsonb and tags @> $11::jsonb");
+        assert_eq!(args, vec![json!("{color}"), json!("red"), json!("{size,width}"), json!("10"), json!("{\"a\":1}"),
+                              json!("{size}"), json!("height"), json!("color"), json!("{tags}"), json!("[\"new\"]"), json!("[1]")]);
+        let (sql, args) = w.build(&DriverType::Mysql);
+        assert_eq!(sql, "json_unquote(json_extract(attrs, ?)) = ? and json_unquote(json_extract(attrs, ?)) = ? and json_contains(attrs, ?) and json_contains_path(attrs, 'one', ?) and json_contains_path(attrs, 'one', ?) and json_contains(attrs, ?, ?) and json_contains(tags, ?, ?)");
+        assert_eq!(args[0], json!("$.color"));
+        assert_eq!(args[3], json!(10));
+        assert_eq!(args[7], json!("\"new\""));
+        assert_eq!(args[8], json!("$.tags"));
+
+        let w = Wrapper::new(&DriverType::Sqlite)
+            .json_contains("attrs", json!({"color": "red", "tags": ["new"]}))
+            .json_has_key("attrs", "items[0]");
+        let (sql, args) = w.build(&DriverType::Sqlite);
+        assert_eq!(sql, "(json_extract(attrs, ?) = ? and exists (select 1 from json_each(attrs, ?) where value = ?)) and json_type(attrs, ?) is not null");
+        assert_eq!(args, vec![json!("$.color"), json!("red"), json!("$.tags"), json!("new"), json!("$.items[0]")]);
+    }
+
+    #[test]
+    fn test_like_escape() {
+        let w = Wrapper::new(&DriverType::Postgres)
+            .like("name", "50%_a!\\")
+            .like_left("name", 1)
+            .not_like("name", "[a]")
+            .like_raw("code", "a_b%")
+            .ilike("title", "Rust");
+        let (sql, args) = w.build(&DriverType::Postgres);
+        assert_eq!(sql, "name like $1 escape '!' and name like $2 escape '!' and name not like $3 escape '!' and code like $4 and title ilike $5 escape '!'");
+        assert_eq!(args, vec![json!("%50!%!_a!!\\%"), json!("%1"), json!("%[a]%"), json!("a_b%"), json!("%Rust%")]);
+        //no backslash,work with NO_BACKSLASH_ESCAPES of mysql
+        let (sql, _) = w.build(&DriverType::Mysql);
+        assert_eq!(sql, "name like ? escape '!' and name like ? escape '!' and name not like ? escape '!' and code like ? and lower(title) like lower(?) escape '!'");
+        let (sql, _) = w.build(&DriverType::Sqlite);
+        assert_eq!(sql.ends_with("lower(title) like lower(?) escape '!'"), true);
+        //'[' is escaped for mssql only
+        let (_, args) = w.build(&DriverType::Mssql);
+        assert_eq!(args[2], json!("%![a]%"));
+        assert_eq!(args[3], json!("a_b%"));
+    }
+
+    #[test]
+    fn test_in_array() {
+        let empty: Vec<i32> = vec![];
+        let w = Wrapper::new(&DriverType::Mysql)
+            .eq("a", 1)
+            .in_array("id", &empty)
+            .or()
+            .not_in("id", &empty);
+        assert_eq!(w.sql(), "a = ? and 1 = 0 or 1 = 1");
+        assert_eq!(w.args(), vec![json!(1)]);
+
+        let ids: Vec<i64> = (0..100).collect();
+        let w = Wrapper::new(&DriverType::Postgres)
+            .eq("a", 1)
+            .in_array("id", &ids)
+            .not_in("user_id", &ids)
+            .in_array("status", &[1, 2]);
+        let (sql, args) = w.build(&DriverType::Postgres);
+        assert_eq!(sql, "a = $1 and id = any($2) and user_id <> all($3) and status in ( $4 , $5 )");
+        assert_eq!(args.len(), 5);
+        assert_eq!(args[1], json!(ids));
+        let (_, args) = w.build(&DriverType::Mysql);
+        assert_eq!(args.len(), 203);
+
+        let mut mixed = vec![json!("a")];
+        mixed.extend(ids.iter().map(|x| json!(x)));
+        let w = Wrapper::new(&DriverType::Postgres).in_array("id", &mixed);
+        assert_eq!(w.args().len(), 101);
+    }
+
+    #[test]
+    fn test_in_array_param_limit() {
+        use rbatis::executor::RbatisRef;
+        use rbatis::rbatis::Rbatis;
+        use rbatis::wrapper::{check_params, max_params, TooManyParams};
+        //not split to (id in (..) or id in (..)),it not reduce the params
+        let names: Vec<String> = (0..1000).map(|x| x.to_string()).collect();
+        let w = Wrapper::new(&DriverType::Sqlite).eq("a", 1).in_array("name", &names);
+        let (sql, args) = w.build(&DriverType::Sqlite);
+        assert!(!sql.contains(" or "));
+        assert_eq!(args.len(), 1001);
+        assert_eq!(check_params(&DriverType::Sqlite, &args), Err(TooManyParams {
+            driver_type: DriverType::Sqlite,
+            len: 1001,
+            max: max_params(&DriverType::Sqlite),
+        }));
+        //checked before bind
+        let rb = Rbatis::new();
+        assert!(rb.bind_arg(&DriverType::Sqlite, &sql, &args).is_err());
+        assert!(check_params(&DriverType::Mysql, &args).is_ok());
+        //integers are inlined
+        let ids: Vec<i64> = (0..1000).collect();
+        let (sql, args) = Wrapper::new(&DriverType::Sqlite).in_array("id", &ids).build(&DriverType::Sqlite);
+        assert!(sql.starts_with("id in ( 0 , 1 ,"));
+        assert!(args.is_empty());
+    }
+
+    static BIND_ARGS: std::sync::Mutex<Vec<serde_json::Value>> = std::sync::Mutex::new(vec![]);
+
+    #[test]
+    fn test_in_array_bind_any() {
+        use rbatis::executor::RbatisRef;
+        use rbatis::rbatis::Rbatis;
+        let mut rb = Rbatis::new();
+        rb.encoder = |_q, arg| {
+            BIND_ARGS.lock().unwrap().push(arg.clone());
+            Ok(())
+        };
+        let names: Vec<String> = (0..200).map(|x| x.to_string()).collect();
+        let w = Wrapper::new(&DriverType::Postgres).eq("a", 1).in_array("name", &names);
+        let (sql, args) = w.build(&DriverType::Postgres);
+        assert_eq!(sql, "a = $1 and name = any($2)");
+        rb.bind_arg(&DriverType::Postgres, &sql, &args).unwrap();
+        //the list is bound as one array arg
+        let bind_args = BIND_ARGS.lock().unwrap();
+        assert_eq!(bind_args.len(), 2);
+        assert_eq!(bind_args[1], json!(names));
+    }
+
+    #[test]
+    fn test_chunk_wrapper() {
+        let w = Wrapper::new(&DriverType::Mysql).eq("a", 1).or().eq("b", 2);
+        let chunk = rbatis::crud::make_chunk_wrapper(&w, "id", &Some(json!(10)), 100).unwrap();
+        let (sql, args) = chunk.build(&DriverType::Mysql);
+        assert_eq!(sql, "(a = ? or b = ?) and id > ? order by id asc limit 100");
+        assert_eq!(args, vec![json!(1), json!(2), json!(10)]);
+        //the first chunk
+        let chunk = rbatis::crud::make_chunk_wrapper(&w, "id", &None, 100).unwrap();
+        assert_eq!(chunk.build(&DriverType::Mysql).0, "(a = ? or b = ?) order by id asc limit 100");
+        let chunk = rbatis::crud::make_chunk_wrapper(&Wrapper::new(&DriverType::Mysql), "id", &Some(json!(10)), 100).unwrap();
+        assert_eq!(chunk.build(&DriverType::Mysql).0, "id > ? order by id asc limit 100");
+    }
 }